@@ -0,0 +1,124 @@
+//! Common representation of the answers returned by the solvers
+
+use geometry::Point;
+use json;
+use registry::SolverError;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+/// The answer to one part of a day
+///
+/// The `Display` implementation prints the answer as it has to be entered on the website.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    Point(Point<i64>),
+}
+
+impl Answer {
+    /// Encode the answer as JSON
+    ///
+    /// Integers become numbers, texts become strings and points become objects with `x` and `y`.
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Integer(i) => i.to_string(),
+            Answer::Text(s) => json::string(s),
+            Answer::Point(p) => json::Object::new()
+                .number("x", p.x)
+                .number("y", p.y)
+                .to_string(),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Answer::Integer(i) => i.fmt(f),
+            Answer::Text(s) => s.fmt(f),
+            Answer::Point(p) => p.fmt(f),
+        }
+    }
+}
+
+/// Return types of solvers, which are either an answer or a `Result` of one
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, SolverError>;
+}
+
+impl<T, E> IntoAnswer for Result<T, E>
+where
+    T: IntoAnswer,
+    E: Error + Send + Sync + 'static,
+{
+    fn into_answer(self) -> Result<Answer, SolverError> {
+        self?.into_answer()
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(i: $t) -> Self {
+                    Answer::Integer(i as i64)
+                }
+            }
+
+            impl From<Point<$t>> for Answer {
+                fn from(p: Point<$t>) -> Self {
+                    Answer::Point(Point::new(p.x as i64, p.y as i64))
+                }
+            }
+
+            impl IntoAnswer for $t {
+                fn into_answer(self) -> Result<Answer, SolverError> {
+                    Ok(Answer::from(self))
+                }
+            }
+
+            impl IntoAnswer for Point<$t> {
+                fn into_answer(self) -> Result<Answer, SolverError> {
+                    Ok(Answer::from(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, isize, u8, u16, u32, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl<'a> From<&'a str> for Answer {
+    fn from(s: &'a str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl IntoAnswer for String {
+    fn into_answer(self) -> Result<Answer, SolverError> {
+        Ok(Answer::from(self))
+    }
+}
+
+#[test]
+fn test_answer() {
+    let answers = [
+        Answer::from(-5i32),
+        Answer::from(42usize),
+        Answer::from("a\nb"),
+        Answer::from(Point::new(57usize, 104)),
+    ];
+    let displayed: Vec<_> = answers.iter().map(Answer::to_string).collect();
+    assert_eq!(displayed, vec!["-5", "42", "a\nb", "57,104"]);
+    let json: Vec<_> = answers.iter().map(Answer::to_json).collect();
+    assert_eq!(json, vec!["-5", "42", r#""a\nb""#, r#"{"x":57,"y":104}"#]);
+}
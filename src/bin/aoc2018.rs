@@ -0,0 +1,611 @@
+//! Command line interface to run the solvers without `cargo aoc`
+
+extern crate advent_of_code_2018;
+extern crate rayon;
+
+use advent_of_code_2018::{
+    json,
+    observer::Reporter,
+    params::Config,
+    random_input,
+    registry::{self, Run, Solver, SolverRun},
+    regression,
+    viewer::{self, Player},
+};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, Read, Write},
+    process,
+    str::FromStr,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Instant,
+};
+
+const USAGE: &str = "Usage:
+    aoc2018 run <day> <part> [--variant <name>] [--input <path>] [--format <format>]
+                [--progress <steps>] [--config <path>] [--set <day<N>.<name>=<value>>...]
+    aoc2018 check <day> [<part>] [--input <path>] [--config <path>] [--set ...]
+    aoc2018 regress [--answers <path>] [--format <format>]
+    aoc2018 all [--inputs <dir>] [--threads <n>] [--format <format>] [--config <path>] [--set ...]
+    aoc2018 view <day> [--input <path>] [--speed <n>]
+    aoc2018 generate <day> [--seed <n>] [--size <n>]
+    aoc2018 list
+
+Commands:
+    run      Solve one part of a day
+    check    Run all variants of a day on the same input and verify they agree
+    regress  Run all solvers on the stored inputs and compare with the expected answers
+    all      Run all solvers concurrently and print them sorted by runtime, slowest first
+    view     Step through the simulation of day 10, 13, or 14 in the terminal, reading
+             commands from stdin, one per line
+    generate Print a random, but valid, input for a day
+    list     Print all solvers and their variants
+
+Options:
+    --variant <name>    Run an alternative implementation instead of the default one
+    --input <path>      Read the puzzle input from <path>, use `-` for stdin
+                        [default: input/2018/day<day>.txt]
+    --answers <path>    File with the expected answers [default: input/2018/answers.txt]
+    --inputs <dir>      Directory with the inputs named day<day>.txt [default: input/2018]
+    --threads <n>       Number of solvers running at the same time, 0 for one per CPU [default: 0]
+    --format <format>   `human` or `json`, which prints one JSON object per solver run
+                        including the generator and solver times [default: human]
+    --progress <steps>  Print the progress of long running solvers to stderr every <steps> steps
+    --config <path>     Read puzzle parameters from a file with lines like `day9.players = 10`
+    --set <assignment>  Override a puzzle parameter, e.g., `--set day7.workers=5`,
+                        takes precedence over the config file and can be repeated
+    --speed <n>         Ticks per second while playing, at most 1024 [default: 4]
+    --seed <n>          Seed of the random input, equal seeds give equal inputs [default: 0]
+    --size <n>          Roughly the number of lines of the random input [default: 100]
+";
+
+/// Exit code for invalid command lines
+const EXIT_USAGE: i32 = 2;
+/// Exit code if the input could not be read or the solver failed
+const EXIT_FAILURE: i32 = 1;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let code = match args.first().map(String::as_str) {
+        Some("run") => cmd_run(&args[1..]),
+        Some("check") => cmd_check(&args[1..]),
+        Some("regress") => cmd_regress(&args[1..]),
+        Some("all") => cmd_all(&args[1..]),
+        Some("view") => cmd_view(&args[1..]),
+        Some("generate") => cmd_generate(&args[1..]),
+        Some("list") => cmd_list(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Some(cmd) => Err(usage_error(format!("Unknown command `{}`", cmd))),
+        None => Err(usage_error("Missing command")),
+    };
+    if let Err(code) = code {
+        process::exit(code);
+    }
+}
+
+fn usage_error(msg: impl AsRef<str>) -> i32 {
+    eprintln!("{}\n\n{}", msg.as_ref(), USAGE);
+    EXIT_USAGE
+}
+
+/// Parsed positional arguments and `--name value` options
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, i32> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| usage_error(format!("Missing value for `{}`", arg)))?;
+                options.push((name.to_string(), value.clone()));
+            } else {
+                positional.push(arg.clone());
+            }
+        }
+        Ok(Args {
+            positional,
+            options,
+        })
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// All values of an option which can be given multiple times
+    fn options<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.options
+            .iter()
+            .filter(move |(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The puzzle parameters from `--config` and `--set`
+    fn config(&self) -> Result<Config, i32> {
+        let mut config = match self.option("config") {
+            Some(path) => {
+                let text = read_file(path).map_err(|err| {
+                    eprintln!("Cannot read config {}: {}", path, err);
+                    EXIT_FAILURE
+                })?;
+                Config::parse(&text).map_err(|err| {
+                    eprintln!("Invalid config {}: {}", path, err);
+                    EXIT_FAILURE
+                })?
+            }
+            None => Config::new(),
+        };
+        for assignment in self.options("set") {
+            config
+                .parse_assignment(assignment)
+                .map_err(|_| usage_error(format!("Invalid --set `{}`", assignment)))?;
+        }
+        registry::validate_config(&config).map_err(|err| {
+            eprintln!("Invalid parameters: {}", err);
+            EXIT_USAGE
+        })?;
+        Ok(config)
+    }
+
+    /// Fail for any options which are not in `known`
+    fn check_options(&self, known: &[&str]) -> Result<(), i32> {
+        match self
+            .options
+            .iter()
+            .find(|(name, _)| !known.contains(&name.as_str()))
+        {
+            Some((name, _)) => Err(usage_error(format!("Unknown option `--{}`", name))),
+            None => Ok(()),
+        }
+    }
+
+    fn format(&self) -> Result<Format, i32> {
+        match self.option("format") {
+            None | Some("human") => Ok(Format::Human),
+            Some("json") => Ok(Format::Json),
+            Some(other) => Err(usage_error(format!("Invalid <format> `{}`", other))),
+        }
+    }
+
+    fn number(&self, idx: usize, name: &str) -> Result<u8, i32> {
+        let value = self
+            .positional
+            .get(idx)
+            .ok_or_else(|| usage_error(format!("Missing <{}>", name)))?;
+        value
+            .parse()
+            .map_err(|_| usage_error(format!("Invalid <{}> `{}`", name, value)))
+    }
+
+    /// Value of a numeric option, or `default` if it is missing
+    fn number_option<T: FromStr>(&self, name: &str, default: T) -> Result<T, i32> {
+        match self.option(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| usage_error(format!("Invalid --{} `{}`", name, value))),
+            None => Ok(default),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Format {
+    Human,
+    Json,
+}
+
+/// Describe a solver run as JSON, the answer is `null` if the solver failed
+fn run_to_json(day: u8, part: u8, variant: &str, actual: Result<&Run, &str>) -> json::Object {
+    let obj = json::Object::new()
+        .number("day", day)
+        .number("part", part)
+        .string("variant", variant);
+    match actual {
+        Ok(run) => obj
+            .raw("answer", run.answer.to_json())
+            .number("generator_ms", regression::millis(run.generator_time))
+            .number("solver_ms", regression::millis(run.solver_time)),
+        Err(err) => obj.null("answer").string("error", err),
+    }
+}
+
+/// Read the input from the path given with `--input`, stdin, or the default location
+fn read_input(path: Option<&str>, day: u8) -> Result<String, i32> {
+    let mut input = String::new();
+    let res = match path {
+        Some("-") => io::stdin().read_to_string(&mut input),
+        Some(path) => File::open(path).and_then(|mut f| f.read_to_string(&mut input)),
+        None => File::open(format!("input/2018/day{}.txt", day))
+            .and_then(|mut f| f.read_to_string(&mut input)),
+    };
+    res.map(|_| input).map_err(|err| {
+        eprintln!("Cannot read input for day {}: {}", day, err);
+        EXIT_FAILURE
+    })
+}
+
+fn cmd_run(args: &[String]) -> Result<(), i32> {
+    let args = Args::parse(args)?;
+    args.check_options(&["variant", "input", "format", "progress", "config", "set"])?;
+    let format = args.format()?;
+    let progress = args.number_option("progress", 0)?;
+    let config = args.config()?;
+    let day = args.number(0, "day")?;
+    let part = args.number(1, "part")?;
+    let variant = args.option("variant");
+
+    let solver = registry::find(day, part, variant).ok_or_else(|| {
+        eprintln!(
+            "No solver for day {} part {}{}",
+            day,
+            part,
+            variant
+                .map(|v| format!(" variant {}", v))
+                .unwrap_or_default()
+        );
+        EXIT_FAILURE
+    })?;
+    let input = read_input(args.option("input"), day)?;
+    let mut print_progress =
+        |steps: usize, summary: &str| eprintln!("[step {}] {}", steps, summary);
+    let mut reporter = if progress > 0 {
+        Reporter::new(progress, &mut print_progress)
+    } else {
+        Reporter::silent()
+    };
+    run_solver(&solver, &input, &config, format, &mut reporter)
+}
+
+fn run_solver(
+    solver: &Solver,
+    input: &str,
+    config: &Config,
+    format: Format,
+    reporter: &mut Reporter,
+) -> Result<(), i32> {
+    let res = solver
+        .run_observed(input, config, reporter)
+        .map_err(|err| err.to_string());
+    match (format, &res) {
+        (Format::Human, Ok(run)) => println!("{}", run.answer),
+        (Format::Human, Err(err)) => eprintln!("{}", err),
+        (Format::Json, _) => println!(
+            "{}",
+            run_to_json(
+                solver.day,
+                solver.part,
+                solver.variant_name(),
+                res.as_ref().map_err(String::as_str)
+            )
+        ),
+    }
+    res.map(|_| ()).map_err(|_| EXIT_FAILURE)
+}
+
+fn cmd_check(args: &[String]) -> Result<(), i32> {
+    let args = Args::parse(args)?;
+    args.check_options(&["input", "config", "set"])?;
+    let config = args.config()?;
+    let day = args.number(0, "day")?;
+    let parts = if args.positional.len() > 1 {
+        vec![args.number(1, "part")?]
+    } else {
+        vec![1, 2]
+    };
+    let input = read_input(args.option("input"), day)?;
+
+    let mut all_agree = true;
+    let mut checked_any = false;
+    for part in parts {
+        let check = registry::cross_check(day, part, &input, &config);
+        if check.results.is_empty() {
+            continue;
+        }
+        checked_any = true;
+        if let Some(answer) = check.agreed_answer() {
+            println!(
+                "day{} part{}: {} variant(s) agree on {}",
+                day,
+                part,
+                check.results.len(),
+                answer
+            );
+            continue;
+        }
+
+        all_agree = false;
+        println!("day{} part{}: variants disagree", day, part);
+        for (variant, res) in &check.results {
+            match res {
+                Ok(answer) => println!("    {:<24} {}", variant, answer),
+                Err(err) => println!("    {:<24} error: {}", variant, err),
+            }
+        }
+    }
+
+    if !checked_any {
+        eprintln!("No solver for day {}", day);
+        Err(EXIT_FAILURE)
+    } else if all_agree {
+        Ok(())
+    } else {
+        Err(EXIT_FAILURE)
+    }
+}
+
+fn read_file(path: &str) -> io::Result<String> {
+    let mut content = String::new();
+    File::open(path)?.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+fn cmd_regress(args: &[String]) -> Result<(), i32> {
+    let args = Args::parse(args)?;
+    args.check_options(&["answers", "format"])?;
+    let format = args.format()?;
+    let path = args.option("answers").unwrap_or("input/2018/answers.txt");
+    let answers = read_file(path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| regression::parse_answers(&text))
+        .map_err(|err| {
+            eprintln!("Cannot read answers from {}: {}", path, err);
+            EXIT_FAILURE
+        })?;
+
+    let results = regression::run(&answers, read_file);
+    match format {
+        Format::Human => print_regression_table(&results),
+        Format::Json => {
+            for res in &results {
+                let actual = res.actual.as_ref().map_err(String::as_str);
+                let obj = run_to_json(res.day, res.part, res.variant, actual)
+                    .string("input", &res.input)
+                    .string("expected", &res.expected)
+                    .number("passed", res.passed());
+                println!("{}", obj);
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|res| !res.passed()).count();
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(EXIT_FAILURE)
+    }
+}
+
+fn print_regression_table(results: &[regression::RegressionResult]) {
+    println!(
+        "{:>3} {:>4}  {:<22} {:<24} {:>11}  result",
+        "day", "part", "variant", "input", "time (ms)"
+    );
+    for res in results {
+        let (time, outcome) = match res.actual {
+            Ok(ref run) => {
+                let time = regression::millis(run.generator_time + run.solver_time);
+                let outcome = if res.passed() {
+                    "ok".to_string()
+                } else {
+                    format!(
+                        "FAIL: expected {:?}, got {:?}",
+                        res.expected,
+                        run.answer.to_string()
+                    )
+                };
+                (format!("{:.3}", time), outcome)
+            }
+            Err(ref err) => ("-".to_string(), format!("FAIL: {}", err)),
+        };
+        println!(
+            "{:>3} {:>4}  {:<22} {:<24} {:>11}  {}",
+            res.day, res.part, res.variant, res.input, time, outcome
+        );
+    }
+
+    let failed = results.iter().filter(|res| !res.passed()).count();
+    println!("\n{} passed, {} failed", results.len() - failed, failed);
+}
+
+fn cmd_all(args: &[String]) -> Result<(), i32> {
+    let args = Args::parse(args)?;
+    args.check_options(&["inputs", "threads", "format", "config", "set"])?;
+    let format = args.format()?;
+    let config = args.config()?;
+    let dir = args.option("inputs").unwrap_or("input/2018");
+    let threads = args.number_option("threads", 0)?;
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+        .map_err(|err| {
+            eprintln!("Cannot start the threads: {}", err);
+            EXIT_FAILURE
+        })?;
+
+    let start = Instant::now();
+    let runs = registry::run_all(&config, |day| read_file(&format!("{}/day{}.txt", dir, day)));
+    let wall_time = start.elapsed();
+    match format {
+        Format::Human => print_run_table(&runs, regression::millis(wall_time)),
+        Format::Json => {
+            for run in &runs {
+                let result = run.result.as_ref().map_err(String::as_str);
+                println!("{}", run_to_json(run.day, run.part, run.variant, result));
+            }
+        }
+    }
+
+    if runs.iter().all(|run| run.result.is_ok()) {
+        Ok(())
+    } else {
+        Err(EXIT_FAILURE)
+    }
+}
+
+fn print_run_table(runs: &[SolverRun], wall_time: f64) {
+    println!(
+        "{:>3} {:>4}  {:<22} {:>14} {:>11} {:>11}  answer",
+        "day", "part", "variant", "generator (ms)", "solver (ms)", "total (ms)"
+    );
+    for run in runs {
+        match run.result {
+            Ok(ref res) => println!(
+                "{:>3} {:>4}  {:<22} {:>14.3} {:>11.3} {:>11.3}  {}",
+                run.day,
+                run.part,
+                run.variant,
+                regression::millis(res.generator_time),
+                regression::millis(res.solver_time),
+                regression::millis(run.time()),
+                single_line(&res.answer.to_string())
+            ),
+            Err(ref err) => println!(
+                "{:>3} {:>4}  {:<22} {:>14} {:>11} {:>11}  error: {}",
+                run.day, run.part, run.variant, "-", "-", "-", err
+            ),
+        }
+    }
+
+    let total: f64 = runs.iter().map(|run| regression::millis(run.time())).sum();
+    let failed = runs.iter().filter(|run| run.result.is_err()).count();
+    println!(
+        "\n{} solvers, {} failed, {:.3} ms total, {:.3} ms wall time",
+        runs.len(),
+        failed,
+        total,
+        wall_time
+    );
+}
+
+/// The answer if it fits on a line, otherwise only the number of lines
+fn single_line(answer: &str) -> String {
+    if answer.contains('\n') {
+        format!("({} lines)", answer.trim().lines().count())
+    } else {
+        answer.to_string()
+    }
+}
+
+/// Clear the terminal and move the cursor to the top left corner
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+fn cmd_view(args: &[String]) -> Result<(), i32> {
+    let args = Args::parse(args)?;
+    args.check_options(&["input", "speed"])?;
+    let day = args.number(0, "day")?;
+    let speed = args.number_option("speed", 4)?;
+    // The commands are read from stdin, which would already be at its end
+    if args.option("input") == Some("-") {
+        return Err(usage_error(
+            "The view command reads its commands from stdin, so it cannot read the input from stdin",
+        ));
+    }
+    let input = read_input(args.option("input"), day)?;
+    let mut animation = viewer::for_day(day, &input)
+        .ok_or_else(|| {
+            eprintln!("No simulation to view for day {}", day);
+            EXIT_FAILURE
+        })?
+        .map_err(|err| {
+            eprintln!("{}", err);
+            EXIT_FAILURE
+        })?;
+
+    // Reading stdin blocks, so the lines are read on their own thread to keep playing meanwhile
+    let (sender, commands) = mpsc::channel();
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            let sent = line.map(|line| sender.send(line).is_ok());
+            if sent.ok() != Some(true) {
+                break;
+            }
+        }
+    });
+
+    let mut player = Player::new(speed);
+    let mut message = String::new();
+    loop {
+        print!(
+            "{}{}\n{}\n",
+            CLEAR_SCREEN,
+            player.frame(&*animation),
+            message
+        );
+        io::stdout().flush().map_err(|_| EXIT_FAILURE)?;
+        let line = if player.playing {
+            match commands.recv_timeout(player.delay()) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    player.advance(&mut *animation);
+                    continue;
+                }
+                // Without more commands, keep playing until the simulation finishes
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(player.delay());
+                    player.advance(&mut *animation);
+                    continue;
+                }
+            }
+        } else {
+            match commands.recv() {
+                Ok(line) => line,
+                Err(_) => break,
+            }
+        };
+        message.clear();
+        match line.parse() {
+            Ok(command) => {
+                if !player.apply(&mut *animation, command) {
+                    break;
+                }
+            }
+            Err(err) => message = err,
+        }
+    }
+    Ok(())
+}
+
+fn cmd_generate(args: &[String]) -> Result<(), i32> {
+    let args = Args::parse(args)?;
+    args.check_options(&["seed", "size"])?;
+    let day = args.number(0, "day")?;
+    let seed = args.number_option("seed", 0)?;
+    let size = args.number_option("size", 100)?;
+    let input = random_input::generate(day, seed, size).ok_or_else(|| {
+        eprintln!("No input generator for day {}", day);
+        EXIT_FAILURE
+    })?;
+    println!("{}", input);
+    Ok(())
+}
+
+fn cmd_list(args: &[String]) -> Result<(), i32> {
+    let args = Args::parse(args)?;
+    args.check_options(&[])?;
+    for solver in registry::solvers() {
+        println!(
+            "day{} part{} {}",
+            solver.day,
+            solver.part,
+            solver.variant_name()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Limits for solvers which search until they find something and might never terminate
+
+use std::{
+    error::Error,
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// How many steps, or how much time, a search may use
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default, Debug)]
+pub struct Budget {
+    /// `None` for no limit
+    pub max_steps: Option<u64>,
+    /// `None` for no limit
+    pub timeout: Option<Duration>,
+}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Budget::default()
+    }
+
+    pub fn steps(max_steps: u64) -> Self {
+        Budget {
+            max_steps: Some(max_steps),
+            timeout: None,
+        }
+    }
+
+    /// Create a budget from the values of puzzle parameters, where 0 means no limit
+    pub fn from_limits(max_steps: u64, timeout_ms: u64) -> Self {
+        Budget {
+            max_steps: if max_steps == 0 {
+                None
+            } else {
+                Some(max_steps)
+            },
+            timeout: if timeout_ms == 0 {
+                None
+            } else {
+                Some(Duration::from_millis(timeout_ms))
+            },
+        }
+    }
+
+    /// Start spending the budget
+    pub fn start(&self) -> Tracker {
+        Tracker {
+            budget: *self,
+            steps: 0,
+            started: Instant::now(),
+        }
+    }
+}
+
+/// Counts the steps of a running search
+#[derive(Copy, Clone, Debug)]
+pub struct Tracker {
+    budget: Budget,
+    steps: u64,
+    started: Instant,
+}
+
+impl Tracker {
+    /// Number of steps the clock is checked after, because reading it is comparatively slow
+    const CLOCK_INTERVAL: u64 = 1024;
+
+    /// Account for one more step, which fails if the budget does not allow it
+    pub fn step(&mut self) -> Result<(), BudgetExceeded> {
+        if let Some(max_steps) = self.budget.max_steps {
+            if self.steps >= max_steps {
+                return Err(BudgetExceeded::Steps(max_steps));
+            }
+        }
+        if let Some(timeout) = self.budget.timeout {
+            if self.steps.is_multiple_of(Self::CLOCK_INTERVAL) && self.started.elapsed() > timeout {
+                return Err(BudgetExceeded::Time {
+                    timeout,
+                    steps: self.steps,
+                });
+            }
+        }
+        self.steps += 1;
+        Ok(())
+    }
+
+    /// Number of steps taken so far
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+}
+
+/// The search did not find a result within its budget
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum BudgetExceeded {
+    Steps(u64),
+    Time { timeout: Duration, steps: u64 },
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            BudgetExceeded::Steps(steps) => {
+                write!(f, "no result found within the budget of {} steps", steps)
+            }
+            BudgetExceeded::Time { timeout, steps } => write!(
+                f,
+                "no result found within the time budget of {} ms, after {} steps",
+                timeout.as_secs() * 1000 + u64::from(timeout.subsec_millis()),
+                steps
+            ),
+        }
+    }
+}
+
+impl Error for BudgetExceeded {}
+
+#[test]
+fn test_budget() {
+    let mut tracker = Budget::steps(3).start();
+    for _ in 0..3 {
+        tracker.step().unwrap();
+    }
+    assert_eq!(tracker.step(), Err(BudgetExceeded::Steps(3)));
+    assert_eq!(tracker.steps(), 3);
+
+    let mut tracker = Budget::from_limits(0, 1).start();
+    ::std::thread::sleep(Duration::from_millis(5));
+    match tracker.step() {
+        Err(BudgetExceeded::Time { steps: 0, .. }) => {}
+        res => panic!("Expected the time budget to be exceeded, got {:?}", res),
+    }
+
+    let mut tracker = Budget::unlimited().start();
+    for _ in 0..10_000 {
+        tracker.step().unwrap();
+    }
+    assert_eq!(
+        BudgetExceeded::Steps(3).to_string(),
+        "no result found within the budget of 3 steps"
+    );
+}
@@ -1,25 +1,376 @@
+//! Day 1: Chronal Calibration
+
+use budget::{Budget, BudgetExceeded};
+use error::{self, ParseError};
+use hashbrown::{HashMap, HashSet};
+use input;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+params! {
+    /// Limits for the search in part 2
+    pub struct Params {
+        /// Maximal number of frequency changes to apply, 0 for no limit
+        max_steps: u64 = 10_000_000,
+        /// Maximal time to search in milliseconds, 0 for no limit
+        timeout_ms: u64 = 0,
+    }
+}
+
+#[aoc_generator(day1)]
+pub fn generator(input: &str) -> Result<Vec<i32>, ParseError> {
+    let input = &input::normalize(input);
+    error::lines(1, input)
+        .map(|line| line.parse(line.text().trim(), "a frequency change"))
+        .collect()
+}
+
 #[aoc(day1, part1)]
-pub fn solve_part1(input: &str) -> i32 {
-    input
-        .lines()
-        .map(|l| l.trim().parse::<i32>().unwrap())
-        .sum()
+pub fn solve_part1(input: &[i32]) -> i32 {
+    input.iter().sum()
 }
 
 #[aoc(day1, part2)]
-pub fn solve_part2(input: &str) -> i32 {
-    let values: Vec<_> = input
-        .lines()
-        .map(|l| l.trim().parse::<i32>().unwrap())
-        .collect();
+pub fn solve_part2(input: &[i32]) -> Result<i32, RepeatError> {
+    solve_part2_with(input, &Params::default())
+}
+
+/// First frequency reached twice, which might never happen
+pub fn solve_part2_with(input: &[i32], params: &Params) -> Result<i32, RepeatError> {
+    // Without any changes, only the initial frequency is reached
+    if input.is_empty() {
+        return Err(RepeatError::NoRepeat);
+    }
+    let mut tracker = Budget::from_limits(params.max_steps, params.timeout_ms).start();
     let mut freq = 0;
-    let mut found_freqs = hashbrown::HashSet::with_capacity(values.len());
-    for v in values.into_iter().cycle() {
+    let mut found_freqs = HashSet::with_capacity(input.len());
+    // The initial frequency counts as reached
+    found_freqs.insert(freq);
+    for v in input.iter().cycle() {
+        tracker.step()?;
         freq += v;
         // returns true if value was inserted, i.e., not in the set
         if !found_freqs.insert(freq) {
-            return freq;
+            return Ok(freq);
         }
     }
     unreachable!()
 }
+
+#[aoc(day1, part2, analytical)]
+pub fn solve_part2_analytical(input: &[i32]) -> Result<i64, RepeatError> {
+    first_repeat(input)
+        .map(|repeat| repeat.frequency)
+        .ok_or(RepeatError::NoRepeat)
+}
+
+/// The first frequency reached twice and when it is reached the second time
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Repeat {
+    pub frequency: i64,
+    /// Number of complete passes over the changes before, starting at 0
+    pub pass: u64,
+    /// Index of the change, which leads to the frequency
+    pub index: usize,
+}
+
+/// Why the search for a repeated frequency failed
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RepeatError {
+    Budget(BudgetExceeded),
+    /// The frequency changes never reach a frequency twice
+    NoRepeat,
+}
+
+impl From<BudgetExceeded> for RepeatError {
+    fn from(err: BudgetExceeded) -> Self {
+        RepeatError::Budget(err)
+    }
+}
+
+impl Display for RepeatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            RepeatError::Budget(err) => err.fmt(f),
+            RepeatError::NoRepeat => write!(f, "no frequency is reached twice"),
+        }
+    }
+}
+
+impl Error for RepeatError {}
+
+/// First frequency reached twice, computed from a single pass over the changes
+///
+/// Before change `j` of pass `p`, the frequency is `prefix[j] + p * drift`, where `prefix[j]` is
+/// the sum of the first `j` changes and `drift` the sum of all. Two prefix sums can only meet if
+/// they are equal modulo the drift. Within such a group, the prefix sum reached next is the
+/// closest one in the direction of the drift. Returns `None` if no frequency repeats.
+pub fn first_repeat<T: Copy + Into<i64>>(changes: &[T]) -> Option<Repeat> {
+    let len = changes.len();
+    if len == 0 {
+        return None;
+    }
+    let mut prefix = Vec::with_capacity(len);
+    let mut frequency = 0;
+    for &change in changes {
+        prefix.push(frequency);
+        frequency += change.into();
+    }
+    let drift = frequency;
+
+    // A frequency reached twice within the first pass comes before all others.
+    // A drift of 0 reaches the initial frequency again after the first pass.
+    let mut seen = HashSet::with_capacity(len);
+    let first_pass = (0..len).find(|&j| !seen.insert(prefix[j]));
+    let time = match (first_pass, drift) {
+        (Some(j), _) => j as u64,
+        (None, 0) => len as u64,
+        (None, _) => {
+            // Measure all prefix sums in the direction of the drift
+            let step = drift.abs();
+            let mut groups: HashMap<i64, Vec<(i64, usize)>> = HashMap::new();
+            for (j, &sum) in prefix.iter().enumerate() {
+                let sum = sum * drift.signum();
+                groups
+                    .entry(((sum % step) + step) % step)
+                    .or_insert_with(Vec::new)
+                    .push((sum, j));
+            }
+            // Each prefix sum catches up with the next larger one of its group after
+            // `(next - sum) / step` passes, the earliest of those is the first repeat
+            groups
+                .values_mut()
+                .flat_map(|group| {
+                    group.sort();
+                    group
+                        .windows(2)
+                        .map(|pair| {
+                            let passes = ((pair[1].0 - pair[0].0) / step) as u64;
+                            passes * len as u64 + pair[0].1 as u64
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .min()?
+        }
+    };
+
+    // `time` counts the frequencies before each change, a repeat is reached by the change before
+    let reached = time - 1;
+    Some(Repeat {
+        frequency: prefix[(time % len as u64) as usize] + (time / len as u64) as i64 * drift,
+        pass: reached / len as u64,
+        index: (reached % len as u64) as usize,
+    })
+}
+
+#[test]
+fn test_part_2_initial_frequency() {
+    assert_eq!(solve_part2(&[1, -1]), Ok(0));
+}
+
+#[test]
+fn test_part_2_empty() {
+    assert_eq!(solve_part2(&[]), Err(RepeatError::NoRepeat));
+    assert_eq!(solve_part2_analytical(&[]), Err(RepeatError::NoRepeat));
+}
+
+#[test]
+fn test_part_2_budget() {
+    let params = Params {
+        max_steps: 100,
+        timeout_ms: 0,
+    };
+    assert_eq!(solve_part2_with(&[3, 3, 4, -2, -4], &params), Ok(10));
+    assert_eq!(
+        solve_part2_with(&[1, 2], &params),
+        Err(RepeatError::Budget(BudgetExceeded::Steps(100)))
+    );
+}
+
+/// Lowest and highest frequency reached during one pass
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PassRange {
+    pub min: i64,
+    pub max: i64,
+}
+
+/// How the frequency develops over the first passes over the changes
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Analysis {
+    /// Change of the frequency by each complete pass
+    pub drift: i64,
+    /// Range of the frequencies reached by the changes of each pass
+    pub passes: Vec<PassRange>,
+    /// Every frequency reached twice, in the order of the second visit
+    ///
+    /// The initial frequency 0 counts as visited once.
+    pub repeats: Vec<Repeat>,
+}
+
+/// Apply the changes `passes` times and collect the statistics of all frequencies reached
+///
+/// The changes are summed as `i64`, such that large inputs do not overflow.
+pub fn analyze<T: Copy + Into<i64>>(changes: &[T], passes: usize) -> Analysis {
+    let mut analysis = Analysis {
+        drift: changes.iter().map(|&change| change.into()).sum(),
+        passes: Vec::with_capacity(passes),
+        repeats: Vec::new(),
+    };
+    if changes.is_empty() {
+        return analysis;
+    }
+
+    // Number of visits of each frequency, only counted up to 2
+    let mut visits = HashMap::with_capacity(changes.len());
+    let mut frequency = 0;
+    visits.insert(frequency, 1);
+    for pass in 0..passes {
+        let mut range = PassRange {
+            min: i64::MAX,
+            max: i64::MIN,
+        };
+        for (index, &change) in changes.iter().enumerate() {
+            frequency += change.into();
+            range.min = range.min.min(frequency);
+            range.max = range.max.max(frequency);
+            let count = visits.entry(frequency).or_insert(0);
+            *count += 1;
+            if *count == 2 {
+                analysis.repeats.push(Repeat {
+                    frequency,
+                    pass: pass as u64,
+                    index,
+                });
+            }
+            *count = (*count).min(2);
+        }
+        analysis.passes.push(range);
+    }
+    analysis
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        writeln!(f, "drift per pass: {:+}", self.drift)?;
+        for (pass, range) in self.passes.iter().enumerate() {
+            writeln!(f, "pass {}: min {}, max {}", pass, range.min, range.max)?;
+        }
+        write!(f, "{} frequencies reached twice", self.repeats.len())?;
+        for repeat in &self.repeats {
+            write!(
+                f,
+                "\n{} in pass {} by change {}",
+                repeat.frequency, repeat.pass, repeat.index
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_first_repeat() {
+    let examples: &[(&[i32], i64)] = &[
+        (&[1, -1], 0),
+        (&[3, 3, 4, -2, -4], 10),
+        (&[-6, 3, 8, 5, -6], 5),
+        (&[7, 7, -2, -7, -4], 14),
+        (&[1, -2, 3, 1], 2),
+    ];
+    for &(changes, frequency) in examples {
+        assert_eq!(solve_part2_analytical(changes), Ok(frequency));
+    }
+    assert_eq!(
+        first_repeat(&[1, -2, 3, 1]),
+        Some(Repeat {
+            frequency: 2,
+            pass: 1,
+            index: 1,
+        })
+    );
+}
+
+#[test]
+fn test_first_repeat_edge_cases() {
+    // A drift of 0 returns to the initial frequency at the end of the first pass
+    assert_eq!(
+        first_repeat(&[2, -2]),
+        Some(Repeat {
+            frequency: 0,
+            pass: 0,
+            index: 1,
+        })
+    );
+    // Far apart prefix sums need many passes, which is fast without cycling
+    assert_eq!(
+        first_repeat(&[1_000_000, -999_999]),
+        Some(Repeat {
+            frequency: 1_000_000,
+            pass: 999_999,
+            index: 1,
+        })
+    );
+    assert_eq!(first_repeat(&[1, 2]), None);
+    assert_eq!(first_repeat::<i32>(&[]), None);
+    assert_eq!(solve_part2_analytical(&[1, 2]), Err(RepeatError::NoRepeat));
+}
+
+#[test]
+fn test_analyze() {
+    let analysis = analyze(&[1, -2, 3, 1], 3);
+    assert_eq!(analysis.drift, 3);
+    assert_eq!(
+        analysis.passes,
+        vec![
+            PassRange { min: -1, max: 3 },
+            PassRange { min: 2, max: 6 },
+            PassRange { min: 5, max: 9 },
+        ]
+    );
+    let repeats: Vec<_> = analysis
+        .repeats
+        .iter()
+        .map(|r| (r.frequency, r.pass, r.index))
+        .collect();
+    assert_eq!(repeats, vec![(2, 1, 1), (5, 2, 1)]);
+    assert_eq!(analysis.repeats[0], first_repeat(&[1, -2, 3, 1]).unwrap());
+    assert_eq!(
+        analysis.to_string(),
+        "drift per pass: +3
+pass 0: min -1, max 3
+pass 1: min 2, max 6
+pass 2: min 5, max 9
+2 frequencies reached twice
+2 in pass 1 by change 1
+5 in pass 2 by change 1"
+    );
+}
+
+#[test]
+fn test_analyze_large_changes() {
+    // The frequency leaves the range of `i32` in the first pass
+    let changes = [i64::from(i32::MAX), 1, -1];
+    let analysis = analyze(&changes, 3);
+    assert_eq!(analysis.drift, i64::from(i32::MAX));
+    assert_eq!(analysis.passes[0].max, i64::from(i32::MAX) + 1);
+    assert_eq!(analysis.passes[2].min, 3 * i64::from(i32::MAX));
+    assert_eq!(analysis.repeats.len(), 3);
+    assert_eq!(analysis.repeats[0].frequency, i64::from(i32::MAX));
+    assert!(analyze::<i32>(&[], 2).passes.is_empty());
+}
+
+#[test]
+fn test_generator_error() {
+    let err = generator("+1\n+x\n").unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::new(1, 2, 1, "a frequency change", "\"+x\"")
+    );
+}
+
+#[test]
+fn test_untidy_input() {
+    let input = input::untidy("+1\n-2\n+3\n+1");
+    assert_eq!(generator(&input).unwrap(), vec![1, -2, 3, 1]);
+}
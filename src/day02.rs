@@ -1,64 +1,174 @@
+//! Day 2: Inventory Management System
+
+use error::NoAnswer;
 use hashbrown::HashMap;
+use input;
+use std::collections::BTreeMap;
 
 #[aoc(day2, part1)]
 pub fn solve_part1(input: &str) -> u32 {
-    let contains_iter = input.lines().map(|l| {
-        // count how often each char exists
-        let mut char_count = HashMap::new();
-        for c in l.trim().chars() {
-            char_count.entry(c).and_modify(|e| *e += 1).or_insert(1);
-        }
+    let input = input::normalize(input);
+    let ids: Vec<&str> = input.lines().map(str::trim).collect();
+    checksum(&ids, &[2, 3]).product() as u32
+}
 
-        // check if it contains any letter two or three times
-        let mut contains_two = false;
-        let mut contains_three = false;
-        for count in char_count.values() {
-            match count {
-                2 => contains_two = true,
-                3 => contains_three = true,
-                _ => {}
-            }
-        }
-        (contains_two, contains_three)
-    });
-
-    // Count how many IDs have a letter exactly two/three times
-    let mut count_twos = 0;
-    let mut count_threes = 0;
-    for (contains_two, contains_three) in contains_iter {
-        if contains_two {
-            count_twos += 1;
-        }
-        if contains_three {
-            count_threes += 1;
-        }
+/// Number of characters with each multiplicity, i.e., how many characters appear exactly once,
+/// exactly twice, and so on
+///
+/// Characters are Unicode scalar values, so any text works as ID.
+pub fn multiplicities(id: &str) -> BTreeMap<usize, usize> {
+    let mut char_count = HashMap::new();
+    for c in id.chars() {
+        *char_count.entry(c).or_insert(0) += 1;
+    }
+    let mut histogram = BTreeMap::new();
+    for &count in char_count.values() {
+        *histogram.entry(count).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Multiplicities of the characters of all IDs
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Checksum {
+    /// For each requested multiplicity, the number of IDs with a character of that multiplicity
+    pub ids_with: BTreeMap<usize, usize>,
+    /// The `multiplicities` of each ID, in the order of the input
+    pub histograms: Vec<BTreeMap<usize, usize>>,
+}
+
+impl Checksum {
+    /// Product of the number of IDs with each requested multiplicity, 1 if none was requested
+    ///
+    /// For the multiplicities 2 and 3 this is the answer to part 1.
+    pub fn product(&self) -> usize {
+        self.ids_with.values().product()
     }
+}
 
-    count_twos * count_threes
+/// Count the IDs with a character appearing exactly `n` times, for each `n` in `multiplicities`
+pub fn checksum<S: AsRef<str>>(ids: &[S], multiplicities: &[usize]) -> Checksum {
+    let histograms: Vec<_> = ids
+        .iter()
+        .map(|id| self::multiplicities(id.as_ref()))
+        .collect();
+    let ids_with = multiplicities
+        .iter()
+        .map(|&n| {
+            let count = histograms
+                .iter()
+                .filter(|histogram| histogram.contains_key(&n))
+                .count();
+            (n, count)
+        })
+        .collect();
+    Checksum {
+        ids_with,
+        histograms,
+    }
 }
 
 #[aoc(day2, part2)]
-pub fn solve_part2(input: &str) -> String {
+pub fn solve_part2(input: &str) -> Result<String, NoAnswer> {
+    let input = input::normalize(input);
+    let ids: Vec<&str> = input.lines().map(str::trim).collect();
+    similar_pairs(&ids)
+        .into_iter()
+        .next()
+        .map(|pair| pair.common)
+        .ok_or_else(|| NoAnswer::new(2, "no two IDs differ in exactly one position"))
+}
+
+#[aoc(day2, part2, pairwise)]
+pub fn solve_part2_pairwise(input: &str) -> Result<String, NoAnswer> {
+    let input = input::normalize(input);
     let ids: Vec<Vec<char>> = input.lines().map(|l| l.trim().chars().collect()).collect();
 
     // for each ID, check if there is another similar one
     for (i, id) in ids.iter().enumerate().skip(1) {
         for other_id in &ids[0..i] {
             if is_similar_id(id, other_id) {
-                return id
+                return Ok(id
                     .iter()
                     .zip(other_id.iter())
                     // only keep identical characters
                     .filter(|(ca, cb)| ca == cb)
                     .map(|(ca, _cb)| ca)
-                    .collect();
+                    .collect());
             }
         }
     }
-    unimplemented!()
+    Err(NoAnswer::new(
+        2,
+        "no two IDs differ in exactly one position",
+    ))
 }
 
-fn is_similar_id(a: &[char], b: &[char]) -> bool {
+/// Two IDs of the same length, which differ in exactly one position
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SimilarPair {
+    /// Index of the earlier ID in the list
+    pub first: usize,
+    /// Index of the later ID in the list
+    pub second: usize,
+    /// Position of the differing character, counted in characters
+    pub position: usize,
+    /// The characters both IDs have in common
+    pub common: String,
+}
+
+/// All pairs of IDs, which differ in exactly one position
+///
+/// Every distinct ID is indexed once for each position, with the character at that position left
+/// out. Two IDs share such a key exactly if they are equal except for that position, so only IDs
+/// within the same bucket are compared. Equal IDs share all keys, which is why repeated IDs are
+/// only indexed once. The pairs are ordered by their later ID, then by their earlier ID, such
+/// that the first pair is the one `solve_part2_pairwise` finds.
+pub fn similar_pairs<S: AsRef<str>>(ids: &[S]) -> Vec<SimilarPair> {
+    // Indices of all occurrences of each distinct ID, in the order of their first occurrence
+    let mut distinct: Vec<(&str, Vec<usize>)> = Vec::new();
+    let mut distinct_index: HashMap<&str, usize> = HashMap::new();
+    for (idx, id) in ids.iter().enumerate() {
+        let id = id.as_ref();
+        let next = distinct.len();
+        let entry = *distinct_index.entry(id).or_insert(next);
+        if entry == next {
+            distinct.push((id, Vec::new()));
+        }
+        distinct[entry].1.push(idx);
+    }
+
+    // The key borrows the text before and after the left out character
+    let mut buckets: HashMap<(usize, &str, &str), Vec<usize>> = HashMap::new();
+    let mut pairs = Vec::new();
+    for (entry, (id, occurrences)) in distinct.iter().enumerate() {
+        for (position, (offset, c)) in id.char_indices().enumerate() {
+            let key = (position, &id[..offset], &id[offset + c.len_utf8()..]);
+            let bucket = buckets.entry(key).or_insert_with(Vec::new);
+            for &other in bucket.iter() {
+                for &a in &distinct[other].1 {
+                    for &b in occurrences {
+                        pairs.push(SimilarPair {
+                            first: a.min(b),
+                            second: a.max(b),
+                            position,
+                            common: format!("{}{}", key.1, key.2),
+                        });
+                    }
+                }
+            }
+            bucket.push(entry);
+        }
+    }
+    pairs.sort_by_key(|pair| (pair.second, pair.first));
+    pairs
+}
+
+/// Whether the two box IDs have the same length and differ in exactly one position
+pub fn is_similar_id(a: &[char], b: &[char]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
     let mut diffs = 0;
     for (ca, cb) in a.iter().zip(b.iter()) {
         if ca != cb {
@@ -71,3 +181,305 @@ fn is_similar_id(a: &[char], b: &[char]) -> bool {
     // only similar if they differ by exactly 1
     diffs == 1
 }
+
+/// Distance between two box IDs
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Metric {
+    /// Number of positions with different characters, see `hamming`
+    Hamming,
+    /// Number of inserted, deleted, or replaced characters, see `levenshtein`
+    Levenshtein,
+}
+
+impl Metric {
+    pub fn distance(self, a: &[char], b: &[char]) -> usize {
+        match self {
+            Metric::Hamming => hamming(a, b),
+            Metric::Levenshtein => levenshtein(a, b),
+        }
+    }
+}
+
+/// Number of positions in which the IDs differ
+///
+/// The characters missing from the shorter ID count as differences, which keeps this a metric for
+/// IDs of different lengths.
+pub fn hamming(a: &[char], b: &[char]) -> usize {
+    let differences = a.iter().zip(b).filter(|(ca, cb)| ca != cb).count();
+    differences + a.len().max(b.len()) - a.len().min(b.len())
+}
+
+/// Smallest number of characters to insert, delete, or replace to turn `a` into `b`
+pub fn levenshtein(a: &[char], b: &[char]) -> usize {
+    // Distances from the prefixes of `a` to the current and the previous prefix of `b`
+    let mut previous: Vec<usize> = (0..=a.len()).collect();
+    let mut current = vec![0; a.len() + 1];
+    for (j, cb) in b.iter().enumerate() {
+        current[0] = j + 1;
+        for (i, ca) in a.iter().enumerate() {
+            let replace = previous[i] + if ca == cb { 0 } else { 1 };
+            current[i + 1] = replace.min(previous[i + 1] + 1).min(current[i] + 1);
+        }
+        ::std::mem::swap(&mut previous, &mut current);
+    }
+    previous[a.len()]
+}
+
+/// An ID within the distance of a query
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Neighbour {
+    pub distance: usize,
+    /// Index of the ID in the list the tree was built from
+    pub index: usize,
+}
+
+/// Burkhard-Keller tree of box IDs, to find all IDs close to another one
+///
+/// Each child is stored with its distance to the parent. By the triangle inequality, a query for
+/// the IDs within `k` of an ID at distance `d` from a node only needs to visit the children with
+/// a distance between `d - k` and `d + k`.
+pub struct BkTree {
+    metric: Metric,
+    nodes: Vec<BkNode>,
+}
+
+struct BkNode {
+    id: Vec<char>,
+    /// Index of the ID in the list the tree was built from
+    index: usize,
+    /// Distance to the parent and index of the child in `nodes`
+    children: Vec<(usize, usize)>,
+}
+
+impl BkTree {
+    pub fn new<S: AsRef<str>>(ids: &[S], metric: Metric) -> Self {
+        let mut tree = BkTree {
+            metric,
+            nodes: Vec::with_capacity(ids.len()),
+        };
+        for (index, id) in ids.iter().enumerate() {
+            tree.insert(index, id.as_ref().chars().collect());
+        }
+        tree
+    }
+
+    fn insert(&mut self, index: usize, id: Vec<char>) {
+        let new = self.nodes.len();
+        if new > 0 {
+            let mut node = 0;
+            loop {
+                let distance = self.metric.distance(&self.nodes[node].id, &id);
+                match self.nodes[node]
+                    .children
+                    .iter()
+                    .find(|&&(d, _)| d == distance)
+                {
+                    Some(&(_, child)) => node = child,
+                    None => {
+                        self.nodes[node].children.push((distance, new));
+                        break;
+                    }
+                }
+            }
+        }
+        self.nodes.push(BkNode {
+            id,
+            index,
+            children: Vec::new(),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// All IDs within `max_distance` of `id`, including equal ones, nearest first
+    pub fn within(&self, id: &str, max_distance: usize) -> Vec<Neighbour> {
+        let id: Vec<char> = id.chars().collect();
+        let mut found = Vec::new();
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            let distance = self.metric.distance(&node.id, &id);
+            if distance <= max_distance {
+                found.push(Neighbour {
+                    distance,
+                    index: node.index,
+                });
+            }
+            let min = distance.saturating_sub(max_distance);
+            let max = distance + max_distance;
+            stack.extend(
+                node.children
+                    .iter()
+                    .filter(|&&(d, _)| min <= d && d <= max)
+                    .map(|&(_, child)| child),
+            );
+        }
+        found.sort();
+        found
+    }
+
+    /// Group the IDs, such that IDs within `max_distance` of each other are in the same cluster
+    ///
+    /// Clusters grow by chaining, so two IDs of a cluster might be further apart. Each cluster
+    /// lists the indices of its IDs in ascending order, the clusters are ordered by their first ID.
+    pub fn clusters(&self, max_distance: usize) -> Vec<Vec<usize>> {
+        let mut cluster_of = vec![None; self.nodes.len()];
+        let mut clusters = Vec::new();
+        // The nodes are in the order of the IDs
+        for start in 0..self.nodes.len() {
+            if cluster_of[start].is_some() {
+                continue;
+            }
+            let cluster = clusters.len();
+            cluster_of[start] = Some(cluster);
+            let mut members = vec![start];
+            let mut todo = vec![start];
+            while let Some(node) = todo.pop() {
+                let id: String = self.nodes[node].id.iter().collect();
+                for neighbour in self.within(&id, max_distance) {
+                    if cluster_of[neighbour.index].is_none() {
+                        cluster_of[neighbour.index] = Some(cluster);
+                        members.push(neighbour.index);
+                        todo.push(neighbour.index);
+                    }
+                }
+            }
+            members.sort();
+            clusters.push(members);
+        }
+        clusters
+    }
+}
+
+#[test]
+fn test_part1() {
+    let input = "abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab";
+    assert_eq!(solve_part1(input), 12);
+}
+
+#[test]
+fn test_checksum() {
+    let ids = ["abcdef", "bababc", "ababab", "ééàé", "日本日本日"];
+    let all = checksum(&ids, &[1, 2, 3, 4]);
+    let expected: BTreeMap<_, _> = vec![(1, 3), (2, 2), (3, 4), (4, 0)].into_iter().collect();
+    assert_eq!(all.ids_with, expected);
+    assert_eq!(all.product(), 0);
+    let histogram = |pairs: &[(usize, usize)]| pairs.iter().cloned().collect::<BTreeMap<_, _>>();
+    assert_eq!(all.histograms[0], histogram(&[(1, 6)]));
+    assert_eq!(all.histograms[1], histogram(&[(1, 1), (2, 1), (3, 1)]));
+    assert_eq!(all.histograms[3], histogram(&[(1, 1), (3, 1)]));
+    assert_eq!(all.histograms[4], histogram(&[(2, 1), (3, 1)]));
+    assert_eq!(checksum(&ids, &[2, 3]).product(), 8);
+    assert_eq!(checksum(&ids, &[]).product(), 1);
+}
+
+#[test]
+fn test_similar_pairs() {
+    let ids = [
+        "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz", "fghij", "äbcde",
+    ];
+    let pairs = similar_pairs(&ids);
+    let summary: Vec<_> = pairs
+        .iter()
+        .map(|pair| (pair.first, pair.second, pair.position, pair.common.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![(1, 4, 2, "fgij"), (4, 7, 2, "fgij"), (0, 8, 0, "bcde"),]
+    );
+    assert!(similar_pairs(&["ab", "abc", "b"]).is_empty());
+
+    // Repeated IDs are compared once, but paired with every occurrence
+    let pairs: Vec<_> = similar_pairs(&["ab", "ab", "ac", "ab", "ac"])
+        .into_iter()
+        .map(|pair| (pair.first, pair.second))
+        .collect();
+    assert_eq!(pairs, vec![(0, 2), (1, 2), (2, 3), (0, 4), (1, 4), (3, 4)]);
+}
+
+#[test]
+fn test_part2() {
+    let input = "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz";
+    assert_eq!(solve_part2(input), Ok("fgij".to_string()));
+    assert_eq!(solve_part2_pairwise(input), Ok("fgij".to_string()));
+    assert_eq!(
+        solve_part2("abc\nabc").unwrap_err().to_string(),
+        "day 2: no answer, no two IDs differ in exactly one position"
+    );
+    assert!(solve_part2("").is_err());
+    assert!(solve_part2_pairwise("abc\nabc").is_err());
+    // IDs of different lengths are not similar, even if their common prefix differs in one position
+    assert!(solve_part2("abc\nxb").is_err());
+    assert!(solve_part2_pairwise("abc\nxb").is_err());
+}
+
+#[test]
+fn test_untidy_input() {
+    let input = input::untidy("abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab");
+    assert_eq!(solve_part1(&input), 12);
+    let input = input::untidy("abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz");
+    assert_eq!(solve_part2(&input), Ok("fgij".to_string()));
+}
+
+#[test]
+fn test_distances() {
+    let chars = |id: &str| id.chars().collect::<Vec<_>>();
+    assert_eq!(hamming(&chars("fghij"), &chars("fguij")), 1);
+    assert_eq!(hamming(&chars("abc"), &chars("abcde")), 2);
+    assert_eq!(hamming(&chars("abc"), &chars("bc")), 3);
+    assert_eq!(levenshtein(&chars("abc"), &chars("bc")), 1);
+    assert_eq!(levenshtein(&chars("kitten"), &chars("sitting")), 3);
+    assert_eq!(levenshtein(&chars(""), &chars("äb")), 2);
+    assert_eq!(
+        Metric::Levenshtein.distance(&chars("fghij"), &chars("fghij")),
+        0
+    );
+}
+
+#[test]
+fn test_bk_tree() {
+    let ids = [
+        "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz", "fghi", "fghij",
+    ];
+    let tree = BkTree::new(&ids, Metric::Hamming);
+    assert_eq!(tree.len(), 9);
+    let indices = |neighbours: Vec<Neighbour>| {
+        neighbours
+            .into_iter()
+            .map(|n| (n.distance, n.index))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        indices(tree.within("fghij", 1)),
+        vec![(0, 1), (0, 8), (1, 4), (1, 7)]
+    );
+    assert_eq!(indices(tree.within("abcde", 2)), vec![(0, 0), (2, 5)]);
+
+    let tree = BkTree::new(&ids, Metric::Levenshtein);
+    assert_eq!(indices(tree.within("ghij", 1)), vec![(1, 1), (1, 8)]);
+    assert_eq!(
+        tree.clusters(1),
+        vec![
+            vec![0],
+            vec![1, 4, 7, 8],
+            vec![2],
+            vec![3],
+            vec![5],
+            vec![6]
+        ]
+    );
+    assert_eq!(tree.clusters(2)[0], vec![0, 5]);
+    assert!(BkTree::new::<&str>(&[], Metric::Hamming)
+        .within("a", 3)
+        .is_empty());
+}
@@ -1,15 +1,26 @@
+//! Day 3: No Matter How You Slice It
+
+use error::{self, NoAnswer, ParseError};
 use hashbrown::HashMap;
+use input;
+use parse::Cursor;
 
+/// A rectangular claim on the fabric, like `#1 @ 55,885: 22x10`
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Claim {
-    id: u32,
-    x: u32,
-    y: u32,
-    width: u32,
-    height: u32,
+    pub id: u32,
+    /// Distance of the left edge to the left edge of the fabric
+    pub x: u32,
+    /// Distance of the top edge to the top edge of the fabric
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
 }
 
 impl Claim {
-    fn iterate_coords(&self) -> impl Iterator<Item = (u32, u32)> {
+    /// All square inches covered by the claim
+    pub fn iterate_coords(&self) -> impl Iterator<Item = (u32, u32)> {
         let y = self.y;
         let height = self.height;
         (self.x..self.x + self.width).flat_map(move |a| (y..y + height).map(move |b| (a, b)))
@@ -17,29 +28,36 @@ impl Claim {
 }
 
 #[aoc_generator(day3)]
-pub fn generator(input: &str) -> Vec<Claim> {
-    input
-        .lines()
-        .map(|l| {
+pub fn generator(input: &str) -> Result<Vec<Claim>, ParseError> {
+    let input = &input::normalize(input);
+    if input.is_empty() {
+        return Err(error::empty_input(3, "a claim"));
+    }
+    error::lines(3, input)
+        .map(|line| {
             // parse a line like: "#1 @ 55,885: 22x10"
-            let mut parts = l.split(' ');
-            let id = parts.next().unwrap()[1..].parse::<u32>().unwrap();
-            // skip @
-            parts.next();
-            let coords = parts.next().unwrap();
-            let coords = &coords[..coords.len() - 1];
-            let x = coords.split(',').next().unwrap().parse::<u32>().unwrap();
-            let y = coords.split(',').nth(1).unwrap().parse::<u32>().unwrap();
-            let size = parts.next().unwrap();
-            let width = size.split('x').next().unwrap().parse::<u32>().unwrap();
-            let height = size.split('x').nth(1).unwrap().parse::<u32>().unwrap();
-            Claim {
+            let mut cursor = Cursor::new(line);
+            cursor.literal("#")?;
+            let id = cursor.integer("a claim id")?;
+            cursor.skip_whitespace();
+            cursor.literal("@")?;
+            cursor.skip_whitespace();
+            let x = cursor.integer("a x coordinate")?;
+            cursor.literal(",")?;
+            let y = cursor.integer("a y coordinate")?;
+            cursor.literal(":")?;
+            cursor.skip_whitespace();
+            let width = cursor.integer("a width")?;
+            cursor.literal("x")?;
+            let height = cursor.integer("a height")?;
+            cursor.end()?;
+            Ok(Claim {
                 id,
                 x,
                 y,
                 width,
                 height,
-            }
+            })
         })
         .collect()
 }
@@ -54,7 +72,7 @@ pub fn solve_part1(input: &Vec<Claim>) -> usize {
 
 #[allow(clippy::ptr_arg)]
 #[aoc(day3, part2)]
-pub fn solve_part2(input: &Vec<Claim>) -> u32 {
+pub fn solve_part2(input: &Vec<Claim>) -> Result<u32, NoAnswer> {
     let counts = count_coords(input);
 
     'claim: for claim in input {
@@ -66,13 +84,14 @@ pub fn solve_part2(input: &Vec<Claim>) -> u32 {
             }
         }
         // only seen 1's
-        return claim.id;
+        return Ok(claim.id);
     }
 
-    unimplemented!()
+    Err(NoAnswer::new(3, "every claim overlaps another one"))
 }
 
-fn count_coords(input: &[Claim]) -> HashMap<(u32, u32), u32> {
+/// Number of claims covering each square inch, which is covered at all
+pub fn count_coords(input: &[Claim]) -> HashMap<(u32, u32), u32> {
     let iter = input.iter().flat_map(|claim| claim.iterate_coords());
     let mut res = HashMap::new();
     for (x, y) in iter {
@@ -86,6 +105,31 @@ fn test_part1() {
     let input = r#"#1 @ 1,3: 4x4
 #2 @ 3,1: 4x4
 #3 @ 5,5: 2x2"#;
-    let res = solve_part1(&generator(input));
+    let res = solve_part1(&generator(input).unwrap());
     assert_eq!(res, 4);
 }
+
+#[test]
+fn test_part2() {
+    let claims = generator("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2").unwrap();
+    assert_eq!(solve_part2(&claims), Ok(3));
+    let claims = generator("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4").unwrap();
+    assert_eq!(
+        solve_part2(&claims),
+        Err(NoAnswer::new(3, "every claim overlaps another one"))
+    );
+}
+
+#[test]
+fn test_untidy_input() {
+    let input = input::untidy("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2");
+    assert_eq!(solve_part1(&generator(&input).unwrap()), 4);
+}
+
+#[test]
+fn test_generator_error() {
+    let err = generator("#1 @ 1,3: 4x4\n#2 @ 3;1: 4x4").unwrap_err();
+    assert_eq!(err, ParseError::new(3, 2, 7, "\",\"", "\";\""));
+    let err = generator(" \n").unwrap_err();
+    assert_eq!(err, ParseError::new(3, 1, 1, "a claim", "end of input"));
+}
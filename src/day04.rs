@@ -1,25 +1,112 @@
+//! Day 4: Repose Record
+
+use error::{self, NoAnswer, ParseError};
 use hashbrown::HashMap;
+use input;
+use parse::Cursor;
 
-#[aoc(day4, part1, hashmap)]
-pub fn solve_part1(input: &str) -> u32 {
-    let mut lines: Vec<_> = input.lines().collect();
-    lines.sort();
+/// What happened at a point in the guard log
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Event {
+    /// The guard with this ID begins the shift
+    BeginsShift(u16),
+    FallsAsleep,
+    WakesUp,
+}
 
-    const PREFIX: &str = "[1518-11-05 00:55] ";
+/// A single entry of the guard log
+///
+/// Only the minute of the timestamp is kept, because all the sleeping happens during the midnight hour.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Record {
+    pub minute: u8,
+    pub event: Event,
+}
+
+#[aoc_generator(day4)]
+pub fn generator(input: &str) -> Result<Vec<Record>, ParseError> {
+    let input = &input::normalize(input);
+    if input.is_empty() {
+        return Err(error::empty_input(4, "a record"));
+    }
+    // The timestamps sort lexicographically, so sorting the lines puts them into chronological order
+    let mut lines: Vec<_> = error::lines(4, input).collect();
+    lines.sort_by_key(|line| line.text());
+
+    // Date and minute at which the current guard fell asleep
+    let mut asleep_since = None;
+    let mut records = Vec::with_capacity(lines.len());
+    for line in lines {
+        // parse a line like: "[1518-11-05 00:55] wakes up"
+        let mut cursor = Cursor::new(line);
+        cursor.literal("[")?;
+        let year = cursor.integer::<u16>("a year")?;
+        cursor.literal("-")?;
+        let month = cursor.integer::<u8>("a month")?;
+        cursor.literal("-")?;
+        let day = cursor.integer::<u8>("a day")?;
+        cursor.skip_whitespace();
+        cursor.integer::<u8>("an hour")?;
+        cursor.literal(":")?;
+        let rest = cursor.rest();
+        let minute = cursor.integer("a minute")?;
+        let minute_token = &rest[..rest.len() - cursor.rest().len()];
+        if minute >= 60 {
+            return Err(line.error_at(minute_token, "a minute below 60"));
+        }
+        cursor.literal("]")?;
+        cursor.skip_whitespace();
+
+        let date = (year, month, day);
+        let event = match cursor.rest() {
+            "falls asleep" => {
+                asleep_since = Some((date, minute));
+                Event::FallsAsleep
+            }
+            "wakes up" => {
+                // The solvers count the minutes in between, which must not be negative
+                match asleep_since.take() {
+                    Some((since_date, since)) if since_date == date && since < minute => {}
+                    Some((_, since)) => {
+                        return Err(line.error_at(
+                            minute_token,
+                            format!(
+                                "a minute after falling asleep on the same day at minute {}",
+                                since
+                            ),
+                        ));
+                    }
+                    None => return Err(line.error_at(cursor.rest(), "\"falls asleep\" first")),
+                }
+                Event::WakesUp
+            }
+            _ => {
+                cursor.literal("Guard #")?;
+                let guard = cursor.integer("a guard id")?;
+                cursor.skip_whitespace();
+                cursor.literal("begins shift")?;
+                cursor.end()?;
+                asleep_since = None;
+                Event::BeginsShift(guard)
+            }
+        };
+        records.push(Record { minute, event });
+    }
+    Ok(records)
+}
+
+#[aoc(day4, part1, hashmap)]
+pub fn solve_part1(input: &[Record]) -> Result<u32, NoAnswer> {
     let mut current_guard = 0;
     let mut current_sleep_start = 0;
     let mut minutes_sleep_per_guard: HashMap<u16, u32> = HashMap::new();
     let mut asleep_per_guard_per_minute: HashMap<(u16, u8), u32> = HashMap::new();
-    for line in &lines {
-        let mut parts = line[PREFIX.len()..].split(' ');
-        match parts.next().unwrap() {
-            "Guard" => {
-                // skip the "#"
-                current_guard = parts.next().unwrap()[1..].parse::<u16>().unwrap()
-            }
-            "falls" => current_sleep_start = parse_minutes(&line),
-            "wakes" => {
-                let end = parse_minutes(&line);
+    for record in input {
+        match record.event {
+            Event::BeginsShift(guard) => current_guard = guard,
+            Event::FallsAsleep => current_sleep_start = record.minute,
+            Event::WakesUp => {
+                let end = record.minute;
                 let sleep = u32::from(end - current_sleep_start);
                 // count total minutes per guard
                 minutes_sleep_per_guard
@@ -35,7 +122,6 @@ pub fn solve_part1(input: &str) -> u32 {
                         .or_insert(1);
                 }
             }
-            _ => unimplemented!(),
         }
     }
 
@@ -43,7 +129,7 @@ pub fn solve_part1(input: &str) -> u32 {
     let (guard, _) = minutes_sleep_per_guard
         .into_iter()
         .max_by_key(|(_guard, minutes)| *minutes)
-        .unwrap();
+        .ok_or_else(no_sleep)?;
     let (minute, _) = (0..60)
         .map(|min| {
             (
@@ -54,31 +140,23 @@ pub fn solve_part1(input: &str) -> u32 {
         .max_by_key(|(_min, count)| *count)
         .unwrap();
 
-    u32::from(guard) * u32::from(minute)
+    Ok(u32::from(guard) * u32::from(minute))
 }
 
 #[aoc(day4, part1, vec)]
-pub fn solve_part1_vec(input: &str) -> u32 {
-    let mut lines: Vec<_> = input.lines().collect();
-    lines.sort();
-
-    const PREFIX: &str = "[1518-11-05 00:55] ";
+pub fn solve_part1_vec(input: &[Record]) -> Result<u32, NoAnswer> {
     let mut current_guard = 0;
     let mut current_sleep_start = 0;
     let mut minutes_sleep_per_guard: HashMap<u16, (u32, Vec<u32>)> = HashMap::new();
-    for line in &lines {
-        let mut parts = line[PREFIX.len()..].split(' ');
-        match parts.next().unwrap() {
-            "Guard" => {
-                // skip the "#"
-                current_guard = parts.next().unwrap()[1..].parse::<u16>().unwrap()
-            }
-            "falls" => current_sleep_start = parse_minutes(&line),
-            "wakes" => {
-                let end = parse_minutes(&line);
+    for record in input {
+        match record.event {
+            Event::BeginsShift(guard) => current_guard = guard,
+            Event::FallsAsleep => current_sleep_start = record.minute,
+            Event::WakesUp => {
+                let end = record.minute;
                 let sleep = u32::from(end - current_sleep_start);
                 // count total minutes per guard
-                let mut entry = minutes_sleep_per_guard
+                let entry = minutes_sleep_per_guard
                     .entry(current_guard)
                     .or_insert_with(|| (0, vec![0; 60]));
 
@@ -88,7 +166,6 @@ pub fn solve_part1_vec(input: &str) -> u32 {
                     (entry.1)[minute as usize] += 1;
                 }
             }
-            _ => unimplemented!(),
         }
     }
 
@@ -96,35 +173,27 @@ pub fn solve_part1_vec(input: &str) -> u32 {
     let (guard, (_total_count, count_per_minute)) = minutes_sleep_per_guard
         .into_iter()
         .max_by_key(|(_guard, (total_minutes, _per_minute))| *total_minutes)
-        .unwrap();
+        .ok_or_else(no_sleep)?;
     let (minute, _count) = count_per_minute
         .into_iter()
         .enumerate()
         .max_by_key(|(_index, count)| *count)
         .unwrap();
 
-    u32::from(guard) * minute as u32
+    Ok(u32::from(guard) * minute as u32)
 }
 
 #[aoc(day4, part2, hashmap)]
-pub fn solve_part2(input: &str) -> u32 {
-    let mut lines: Vec<_> = input.lines().collect();
-    lines.sort();
-
-    const PREFIX: &str = "[1518-11-05 00:55] ";
+pub fn solve_part2(input: &[Record]) -> Result<u32, NoAnswer> {
     let mut current_guard = 0;
     let mut current_sleep_start = 0;
     let mut asleep_per_guard_per_minute: HashMap<(u16, u8), u32> = HashMap::new();
-    for line in &lines {
-        let mut parts = line[PREFIX.len()..].split(' ');
-        match parts.next().unwrap() {
-            "Guard" => {
-                // skip the "#"
-                current_guard = parts.next().unwrap()[1..].parse::<u16>().unwrap()
-            }
-            "falls" => current_sleep_start = parse_minutes(&line),
-            "wakes" => {
-                let end = parse_minutes(&line);
+    for record in input {
+        match record.event {
+            Event::BeginsShift(guard) => current_guard = guard,
+            Event::FallsAsleep => current_sleep_start = record.minute,
+            Event::WakesUp => {
+                let end = record.minute;
                 // count for each guard and each minute
                 for minute in current_sleep_start..end {
                     asleep_per_guard_per_minute
@@ -133,38 +202,29 @@ pub fn solve_part2(input: &str) -> u32 {
                         .or_insert(1);
                 }
             }
-            _ => unimplemented!(),
         }
     }
 
     let ((guard, minute), _count) = asleep_per_guard_per_minute
         .into_iter()
         .max_by_key(|((_guard, _minute), count)| *count)
-        .unwrap();
+        .ok_or_else(no_sleep)?;
 
-    u32::from(guard) * u32::from(minute)
+    Ok(u32::from(guard) * u32::from(minute))
 }
 
 #[aoc(day4, part2, vec)]
-pub fn solve_part2_vec(input: &str) -> u32 {
-    let mut lines: Vec<_> = input.lines().collect();
-    lines.sort();
-
-    const PREFIX: &str = "[1518-11-05 00:55] ";
+pub fn solve_part2_vec(input: &[Record]) -> Result<u32, NoAnswer> {
     let mut current_guard = 0;
     let mut current_sleep_start = 0;
     let mut asleep_per_guard_per_minute: HashMap<u16, Vec<u32>> = HashMap::new();
-    for line in &lines {
-        let mut parts = line[PREFIX.len()..].split(' ');
-        match parts.next().unwrap() {
-            "Guard" => {
-                // skip the "#"
-                current_guard = parts.next().unwrap()[1..].parse::<u16>().unwrap()
-            }
-            "falls" => current_sleep_start = parse_minutes(&line),
-            "wakes" => {
-                let end = parse_minutes(&line);
-                let mut entry = asleep_per_guard_per_minute
+    for record in input {
+        match record.event {
+            Event::BeginsShift(guard) => current_guard = guard,
+            Event::FallsAsleep => current_sleep_start = record.minute,
+            Event::WakesUp => {
+                let end = record.minute;
+                let entry = asleep_per_guard_per_minute
                     .entry(current_guard)
                     .or_insert_with(|| vec![0; 60]);
                 // count for each guard and each minute
@@ -172,7 +232,6 @@ pub fn solve_part2_vec(input: &str) -> u32 {
                     entry[minute as usize] += 1
                 }
             }
-            _ => unimplemented!(),
         }
     }
 
@@ -185,14 +244,13 @@ pub fn solve_part2_vec(input: &str) -> u32 {
                 .map(move |(minute, count)| (guard, minute as u32, count))
         })
         .max_by_key(|(_guard, _minute, count)| *count)
-        .unwrap();
+        .ok_or_else(no_sleep)?;
 
-    u32::from(guard) * minute
+    Ok(u32::from(guard) * minute)
 }
 
-fn parse_minutes(line: &str) -> u8 {
-    const PREFIX: &str = "[1518-11-05 00:";
-    line[PREFIX.len()..PREFIX.len() + 2].parse::<u8>().unwrap()
+fn no_sleep() -> NoAnswer {
+    NoAnswer::new(4, "no guard ever falls asleep")
 }
 
 #[cfg(test)]
@@ -217,24 +275,83 @@ const TEST_INPUT: &str = r#"[1518-11-01 00:00] Guard #10 begins shift
 
 #[test]
 fn test_part_1() {
-    let res = solve_part1(TEST_INPUT);
-    assert_eq!(res, 240);
+    let res = solve_part1(&generator(TEST_INPUT).unwrap());
+    assert_eq!(res, Ok(240));
+}
+
+#[test]
+fn test_untidy_input() {
+    let res = solve_part1(&generator(&input::untidy(TEST_INPUT)).unwrap());
+    assert_eq!(res, Ok(240));
 }
 
 #[test]
 fn test_part_1_vec() {
-    let res = solve_part1_vec(TEST_INPUT);
-    assert_eq!(res, 240);
+    let res = solve_part1_vec(&generator(TEST_INPUT).unwrap());
+    assert_eq!(res, Ok(240));
 }
 
 #[test]
 fn test_part_2() {
-    let res = solve_part2(TEST_INPUT);
-    assert_eq!(res, 4455);
+    let res = solve_part2(&generator(TEST_INPUT).unwrap());
+    assert_eq!(res, Ok(4455));
 }
 
 #[test]
 fn test_part_2_vec() {
-    let res = solve_part2_vec(TEST_INPUT);
-    assert_eq!(res, 4455);
+    let res = solve_part2_vec(&generator(TEST_INPUT).unwrap());
+    assert_eq!(res, Ok(4455));
+}
+
+#[test]
+fn test_generator_error() {
+    let err =
+        generator("[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] falls awake")
+            .unwrap_err();
+    assert_eq!(err, ParseError::new(4, 2, 20, "\"Guard #\"", "\"falls a\""));
+    let err = generator("").unwrap_err();
+    assert_eq!(err, ParseError::new(4, 1, 1, "a record", "end of input"));
+}
+
+#[test]
+fn test_generator_invalid_sleep() {
+    let err = generator("[1518-11-01 00:75] Guard #10 begins shift").unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::new(4, 1, 16, "a minute below 60", "\"75\"")
+    );
+
+    let err = generator(
+        "[1518-11-01 00:00] Guard #10 begins shift\n\
+         [1518-11-01 00:30] falls asleep\n\
+         [1518-11-02 00:10] wakes up",
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::new(
+            4,
+            3,
+            16,
+            "a minute after falling asleep on the same day at minute 30",
+            "\"10\""
+        )
+    );
+
+    let err = generator("[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:10] wakes up")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::new(4, 2, 20, "\"falls asleep\" first", "\"wakes up\"")
+    );
+}
+
+#[test]
+fn test_no_sleep() {
+    let records = generator("[1518-11-01 00:00] Guard #10 begins shift").unwrap();
+    let expected = Err(NoAnswer::new(4, "no guard ever falls asleep"));
+    assert_eq!(solve_part1(&records), expected);
+    assert_eq!(solve_part1_vec(&records), expected);
+    assert_eq!(solve_part2(&records), expected);
+    assert_eq!(solve_part2_vec(&records), expected);
 }
@@ -1,15 +1,19 @@
+//! Day 5: Alchemical Reduction
+
+use input;
 use misc_utils::Min;
 use rayon::prelude::*;
 
 #[aoc(day5, part1)]
 pub fn solve_part1(input: &str) -> usize {
-    let unmatched_units = collapse_polymer(input.trim_end().chars());
+    let input = input::normalize(input);
+    let unmatched_units = collapse_polymer(input.chars());
     unmatched_units.len()
 }
 
 #[aoc(day5, part2)]
 pub fn solve_part2(input: &str) -> usize {
-    let input = input.trim_end();
+    let input = input::normalize(input);
 
     let mut min_length = Min::new();
     // Test which char is the most beneficial to remove
@@ -23,7 +27,7 @@ pub fn solve_part2(input: &str) -> usize {
 
 #[aoc(day5, part2, iterator)]
 pub fn solve_part2_iterator(input: &str) -> usize {
-    let input = input.trim_end();
+    let input = input::normalize(input);
 
     // Test which char is the most beneficial to remove
     "abcdefghijklmnopqrstuvwxyz"
@@ -38,7 +42,7 @@ pub fn solve_part2_iterator(input: &str) -> usize {
 
 #[aoc(day5, part2, rayon)]
 pub fn solve_part2_par(input: &str) -> usize {
-    let input = input.trim_end();
+    let input = input::normalize(input);
 
     // Test which char is the most beneficial to remove
     "abcdefghijklmnopqrstuvwxyz"
@@ -53,7 +57,7 @@ pub fn solve_part2_par(input: &str) -> usize {
 
 #[aoc(day5, part2, early_collapse)]
 pub fn solve_part2_early_collapse(input: &str) -> usize {
-    let input = input.trim_end();
+    let input = input::normalize(input);
     let early_collapse = collapse_polymer(input.chars());
 
     // Test which char is the most beneficial to remove
@@ -75,7 +79,7 @@ pub fn solve_part2_early_collapse(input: &str) -> usize {
 
 #[aoc(day5, part2, rayon_early_collapse)]
 pub fn solve_part2_par_early_collapse(input: &str) -> usize {
-    let input = input.trim_end();
+    let input = input::normalize(input);
     let early_collapse = collapse_polymer(input.chars());
 
     // Test which char is the most beneficial to remove
@@ -95,7 +99,8 @@ pub fn solve_part2_par_early_collapse(input: &str) -> usize {
         .unwrap()
 }
 
-fn collapse_polymer(polymer: impl IntoIterator<Item = char>) -> Vec<char> {
+/// Let all adjacent units of the same type and opposite polarity react and return the remaining units
+pub fn collapse_polymer(polymer: impl IntoIterator<Item = char>) -> Vec<char> {
     let mut unmatched_units = Vec::new();
     for unit in polymer {
         if let Some(&last_unit) = unmatched_units.last() {
@@ -114,11 +119,11 @@ fn collapse_polymer(polymer: impl IntoIterator<Item = char>) -> Vec<char> {
     unmatched_units
 }
 
-fn do_units_match(unit_a: char, unit_b: char) -> bool {
+/// Whether the two units react, i.e., they are the same letter in different cases
+pub fn do_units_match(unit_a: char, unit_b: char) -> bool {
     // One is uppercase while the other one is lowercase
     // AND they are they same kind of unit (same letter)
-    unit_a.is_uppercase() ^ unit_b.is_uppercase()
-        && unit_a.to_ascii_lowercase() == unit_b.to_ascii_lowercase()
+    unit_a.is_uppercase() ^ unit_b.is_uppercase() && unit_a.eq_ignore_ascii_case(&unit_b)
 }
 
 #[cfg(test)]
@@ -131,6 +136,12 @@ fn test_part_1_a() {
     assert_eq!(res, 6);
 }
 
+#[test]
+fn test_untidy_input() {
+    assert_eq!(solve_part1(&input::untidy(TEST_INPUT)), 10);
+    assert_eq!(solve_part2(&input::untidy(TEST_INPUT)), 4);
+}
+
 #[test]
 fn test_part_1_b() {
     let res = solve_part1(TEST_INPUT);
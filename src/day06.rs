@@ -1,241 +1,174 @@
+//! Day 6: Chronal Coordinates
+
+use error::{self, NoAnswer, ParseError};
+use geometry::{BoundingBox, Point};
+use grid::Grid;
 use hashbrown::HashMap;
-use misc_utils::{Max, Min};
+use input;
+use misc_utils::Min;
+use parse::Cursor;
 use rayon::prelude::*;
 
-pub struct Coordinate {
-    id: usize,
-    x: i32,
-    y: i32,
+params! {
+    /// Parameters of the area search
+    pub struct Params {
+        /// Part 2 counts the points whose summed distance to all coordinates is below this limit
+        distance_limit: usize = 10000,
+    }
 }
 
-impl Coordinate {
-    fn distance(&self, other: &Coordinate) -> u32 {
-        (self.x - other.x).abs() as u32 + (self.y - other.y).abs() as u32
-    }
+/// One of the coordinates from the puzzle input
+pub struct Coordinate {
+    /// Index of the coordinate in the input
+    pub id: usize,
+    pub position: Point<i32>,
 }
 
 #[aoc_generator(day6)]
-pub fn generator(input: &str) -> Vec<Coordinate> {
-    input
-        .lines()
+pub fn generator(input: &str) -> Result<Vec<Coordinate>, ParseError> {
+    let input = &input::normalize(input);
+    if input.is_empty() {
+        return Err(error::empty_input(6, "a coordinate"));
+    }
+    error::lines(6, input)
         .enumerate()
-        .map(|(id, l)| {
-            let mut iter = l.split(", ");
-            let x = iter.next().unwrap().parse().unwrap();
-            let y = iter.next().unwrap().parse().unwrap();
-            Coordinate { id, x, y }
+        .map(|(id, line)| {
+            // parse a line like: "1, 6"
+            let mut cursor = Cursor::new(line);
+            let x = cursor.integer("a x coordinate")?;
+            cursor.literal(",")?;
+            cursor.skip_whitespace();
+            let y = cursor.integer("a y coordinate")?;
+            cursor.end()?;
+            Ok(Coordinate {
+                id,
+                position: Point::new(x, y),
+            })
         })
         .collect()
 }
 
 #[aoc(day6, part1)]
-pub fn solve_part1(input: &[Coordinate]) -> usize {
-    let (min_x, max_x, min_y, max_y) = get_bounding_box(input);
+pub fn solve_part1(input: &[Coordinate]) -> Result<usize, NoAnswer> {
+    let bbox = get_bounding_box(input)?;
 
     // Lets work with the assumption, that every region which touches the bounding box escapes into infinity
     // Thus everything at the bounding box must be discarded later
     // https://www.reddit.com/r/adventofcode/comments/a3kr4r/2018_day_6_solutions/eb7axrw/
 
-    // Calculate the full grid
-    let size_x = max_x - min_x + 1;
-    let size_y = max_y - min_y + 1;
-
     // grid is a matrix which stores the closest ID or None if there is no closest
-    let mut grid: Vec<Option<usize>> = vec![None; (size_x * size_y) as usize];
-
-    let set_cell = |grid: &mut Vec<Option<usize>>, x: i32, y: i32, value: Option<usize>| {
-        grid[((x - min_x) + (y - min_y) * size_x) as usize] = value;
-    };
-    let get_cell = |grid: &Vec<Option<usize>>, x: i32, y: i32| -> Option<usize> {
-        grid[((x - min_x) + (y - min_y) * size_x) as usize]
-    };
-
-    for x in min_x..=max_x {
-        for y in min_y..=max_y {
-            let c = Coordinate {
-                id: usize::max_value(),
-                x,
-                y,
-            };
-            let mut closest_dist = Min::with_initial(c.distance(&input[0]));
-            let mut closest_id = Some(input[0].id);
-            for coord in &input[1..] {
-                let d = c.distance(coord);
-                let cd = closest_dist.get_min_extreme();
-                if d == cd {
-                    // found two closests, remove value
-                    closest_id = None;
-                } else if d < cd {
-                    closest_dist.update(d);
-                    closest_id = Some(coord.id);
-                }
-            }
-            set_cell(&mut grid, x, y, closest_id);
-        }
-    }
-
-    let mut area_counter: HashMap<usize, usize> = HashMap::new();
-    for id in &grid {
-        if let Some(id) = id {
-            area_counter.entry(*id).and_modify(|e| *e += 1).or_insert(1);
-        }
-    }
-
-    // Check bounding box and remove those from the area counter
-    // upper edge / lower edge
-    for x in min_x..=max_x {
-        // upper
-        if let Some(id) = get_cell(&grid, x, min_y) {
-            area_counter.remove(&id);
-        }
-        // lower
-        if let Some(id) = get_cell(&grid, x, max_y) {
-            area_counter.remove(&id);
-        }
-    }
-    // left edge / right edge
-    for y in min_y..=max_y {
-        // left
-        if let Some(id) = get_cell(&grid, min_x, y) {
-            area_counter.remove(&id);
-        }
-        // right
-        if let Some(id) = get_cell(&grid, max_x, y) {
-            area_counter.remove(&id);
-        }
-    }
-
-    area_counter.values().cloned().max().unwrap()
+    let grid = Grid::from_fn(
+        bbox.min.x as isize,
+        bbox.min.y as isize,
+        bbox.width() as usize,
+        bbox.height() as usize,
+        |pos| find_closest(input, Point::new(pos.x as i32, pos.y as i32)),
+    );
+
+    count_finite_areas(&grid).ok_or_else(|| NoAnswer::new(6, "all areas are infinite"))
 }
 
 #[aoc(day6, part1, rayon)]
-pub fn solve_part1_rayon(input: &[Coordinate]) -> usize {
-    let (min_x, max_x, min_y, max_y) = get_bounding_box(input);
+pub fn solve_part1_rayon(input: &[Coordinate]) -> Result<usize, NoAnswer> {
+    let bbox = get_bounding_box(input)?;
 
     // Lets work with the assumption, that every region which touches the bounding box escapes into infinity
     // Thus everything at the bounding box must be discarded later
     // https://www.reddit.com/r/adventofcode/comments/a3kr4r/2018_day_6_solutions/eb7axrw/
 
-    // Calculate the full grid
-    let size_x = max_x - min_x + 1;
-
     // grid is a matrix which stores the closest ID or None if there is no closest
-    #[allow(clippy::range_plus_one)]
-    let grid: Vec<Option<usize>> = (min_y..max_y + 1)
+    let cells: Vec<Option<usize>> = bbox
+        .points()
+        .collect::<Vec<_>>()
         .into_par_iter()
-        .flat_map(|y| (min_x..max_x + 1).into_par_iter().map(move |x| (x, y)))
-        .map(|(x, y)| {
-            let c = Coordinate {
-                id: usize::max_value(),
-                x,
-                y,
-            };
-            let mut closest_dist = Min::with_initial(c.distance(&input[0]));
-            let mut closest_id = Some(input[0].id);
-            for coord in &input[1..] {
-                let d = c.distance(coord);
-                let cd = closest_dist.get_min_extreme();
-                if d == cd {
-                    // found two closests, remove value
-                    closest_id = None;
-                } else if d < cd {
-                    closest_dist.update(d);
-                    closest_id = Some(coord.id);
-                }
-            }
-            closest_id
-        })
+        .map(|point| find_closest(input, point))
         .collect();
-    let get_cell = |grid: &Vec<Option<usize>>, x: i32, y: i32| -> Option<usize> {
-        grid[((x - min_x) + (y - min_y) * size_x) as usize]
-    };
+    let grid = Grid::from_vec(
+        bbox.min.x as isize,
+        bbox.min.y as isize,
+        bbox.width() as usize,
+        bbox.height() as usize,
+        cells,
+    );
+
+    count_finite_areas(&grid).ok_or_else(|| NoAnswer::new(6, "all areas are infinite"))
+}
 
+/// Returns the ID of the closest coordinate or None if there are multiple equally close ones
+pub fn find_closest(input: &[Coordinate], point: Point<i32>) -> Option<usize> {
+    let mut closest_dist = Min::with_initial(point.manhattan(input[0].position));
+    let mut closest_id = Some(input[0].id);
+    for coord in &input[1..] {
+        let d = point.manhattan(coord.position);
+        let cd = closest_dist.get_min_extreme();
+        if d == cd {
+            // found two closests, remove value
+            closest_id = None;
+        } else if d < cd {
+            closest_dist.update(d);
+            closest_id = Some(coord.id);
+        }
+    }
+    closest_id
+}
+
+/// Size of the largest area, which does not touch the edge of the grid
+///
+/// Returns `None` if all areas touch the edge, i.e., are infinite.
+pub fn count_finite_areas(grid: &Grid<Option<usize>>) -> Option<usize> {
     let mut area_counter: HashMap<usize, usize> = HashMap::new();
-    for id in &grid {
+    for (_, id) in grid.iter() {
         if let Some(id) = id {
             area_counter.entry(*id).and_modify(|e| *e += 1).or_insert(1);
         }
     }
 
     // Check bounding box and remove those from the area counter
-    // upper edge / lower edge
-    for x in min_x..=max_x {
-        // upper
-        if let Some(id) = get_cell(&grid, x, min_y) {
-            area_counter.remove(&id);
-        }
-        // lower
-        if let Some(id) = get_cell(&grid, x, max_y) {
-            area_counter.remove(&id);
-        }
-    }
-    // left edge / right edge
-    for y in min_y..=max_y {
-        // left
-        if let Some(id) = get_cell(&grid, min_x, y) {
-            area_counter.remove(&id);
-        }
-        // right
-        if let Some(id) = get_cell(&grid, max_x, y) {
-            area_counter.remove(&id);
-        }
+    let edges = grid
+        .row(grid.min_y())
+        .chain(grid.row(grid.max_y()))
+        .chain(grid.column(grid.min_x()))
+        .chain(grid.column(grid.max_x()));
+    for id in edges.flatten() {
+        area_counter.remove(id);
     }
 
-    area_counter.values().cloned().max().unwrap()
+    area_counter.values().cloned().max()
 }
 
-fn get_bounding_box(input: &[Coordinate]) -> (i32, i32, i32, i32) {
-    // Determine bounding box for Coordinates
-    let mut min_x = Min::new();
-    let mut max_x = Max::new();
-    let mut min_y = Min::new();
-    let mut max_y = Max::new();
-    for coord in input {
-        min_x.update(coord.x);
-        max_x.update(coord.x);
-        min_y.update(coord.y);
-        max_y.update(coord.y);
-    }
-    (
-        min_x.get_min_extreme(),
-        max_x.get_max_extreme(),
-        min_y.get_min_extreme(),
-        max_y.get_max_extreme(),
-    )
+/// Smallest bounding box containing all coordinates
+pub fn get_bounding_box(input: &[Coordinate]) -> Result<BoundingBox<i32>, NoAnswer> {
+    BoundingBox::from_points(input.iter().map(|coord| coord.position))
+        .ok_or_else(|| NoAnswer::new(6, "there are no coordinates"))
 }
 
 #[aoc(day6, part2)]
-pub fn solve_part2(input: &[Coordinate]) -> usize {
-    const DISTANCE_LIMIT: usize = 10000;
+pub fn solve_part2(input: &[Coordinate]) -> Result<usize, NoAnswer> {
+    solve_part2_with(input, &Params::default())
+}
 
-    let (min_x, max_x, min_y, max_y) = get_bounding_box(input);
-    // There is an absolute limit of DISTANCE_LIMIT
-    // So everything outside the bounding box by an amount larger than DISTANCE_LIMIT/input.len()
+pub fn solve_part2_with(input: &[Coordinate], params: &Params) -> Result<usize, NoAnswer> {
+    let distance_limit = params.distance_limit;
+    // There is an absolute limit of distance_limit
+    // So everything outside the bounding box by an amount larger than distance_limit/input.len()
     // must have a longer distance
     // "/ input.len()", because the distances to all coordinates are summed together
-    let min_x = min_x - (DISTANCE_LIMIT / input.len()) as i32;
-    let max_x = max_x + (DISTANCE_LIMIT / input.len()) as i32;
-    let min_y = min_y - (DISTANCE_LIMIT / input.len()) as i32;
-    let max_y = max_y + (DISTANCE_LIMIT / input.len()) as i32;
+    let bbox = get_bounding_box(input)?.expand((distance_limit / input.len()) as i32);
 
-    // true if the sum of distances to all points is below DISTANCE_LIMIT
-    #[allow(clippy::range_plus_one)]
-    (min_y..max_y + 1)
+    // true if the sum of distances to all points is below distance_limit
+    let count = bbox
+        .points()
+        .collect::<Vec<_>>()
         .into_par_iter()
-        .flat_map(|y| (min_x..max_x + 1).into_par_iter().map(move |x| (x, y)))
-        .filter(|(x, y)| {
-            let c = Coordinate {
-                id: usize::max_value(),
-                x: *x,
-                y: *y,
-            };
+        .filter(|point| {
             input
                 .par_iter()
-                .map(|coord| c.distance(coord) as usize)
+                .map(|coord| point.manhattan(coord.position) as usize)
                 .sum::<usize>()
-                < DISTANCE_LIMIT
+                < distance_limit
         })
-        .count()
+        .count();
+    Ok(count)
 }
 
 #[cfg(test)]
@@ -249,14 +182,38 @@ const TEST_INPUT: &str = r#"1, 1
 
 #[test]
 fn test_part_1() {
-    let processed = generator(TEST_INPUT);
+    let processed = generator(TEST_INPUT).unwrap();
     let res = solve_part1(&processed);
-    assert_eq!(res, 17)
+    assert_eq!(res, Ok(17))
+}
+
+#[test]
+fn test_untidy_input() {
+    let processed = generator(&input::untidy(TEST_INPUT)).unwrap();
+    assert_eq!(solve_part1(&processed), Ok(17))
+}
+
+#[test]
+fn test_part_2_with() {
+    // The example uses a limit of 32 instead of 10000
+    let processed = generator(TEST_INPUT).unwrap();
+    let res = solve_part2_with(&processed, &Params { distance_limit: 32 });
+    assert_eq!(res, Ok(16))
 }
 
 #[test]
-fn test_part_2() {
-    let processed = generator(TEST_INPUT);
-    let res = solve_part2(&processed);
-    assert_eq!(res, 16)
+fn test_no_answer() {
+    // With only two coordinates, both areas are infinite
+    let processed = generator("1, 1\n5, 2").unwrap();
+    let expected = Err(NoAnswer::new(6, "all areas are infinite"));
+    assert_eq!(solve_part1(&processed), expected);
+    assert_eq!(solve_part1_rayon(&processed), expected);
+    assert_eq!(
+        solve_part2(&[]),
+        Err(NoAnswer::new(6, "there are no coordinates"))
+    );
+    assert_eq!(
+        generator("").err(),
+        Some(ParseError::new(6, 1, 1, "a coordinate", "end of input"))
+    );
 }
@@ -1,27 +1,93 @@
+//! Day 7: The Sum of Its Parts
+
+use error::{self, NoAnswer, ParseError};
+use input;
 use misc_utils::Max;
+use observer::{Observer, Silent, Summary};
+use parse::Cursor;
+
+params! {
+    /// Parameters of the assembly in part 2
+    pub struct Params {
+        /// Number of workers, which can each work on one step at a time
+        workers: u8 = 15,
+        /// Extra seconds each step takes, on top of its position in the alphabet
+        base_step_cost: u8 = 60,
+    }
 
-const PREFIX1: usize = "Step ".len();
-const PREFIX2: usize = "Step Y must be finished before step ".len();
+    validate(params) {
+        if params.workers == 0 {
+            return Err(::params::Invalid::new("workers", "at least one worker is needed"));
+        }
+        Ok(())
+    }
+}
 
+/// `step` can only begin after `depends_on` is finished
+///
+/// Steps are stored as index starting at 0 for step `A`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct Instruction {
-    step: u8,
-    depends_on: u8,
+    pub step: u8,
+    pub depends_on: u8,
+}
+
+/// What the workers do during one second of part 2
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Workers {
+    pub second: usize,
+    /// Steps currently worked on, in alphabetical order
+    pub in_progress: Vec<u8>,
+    /// Seconds of work left for each step, before this second
+    pub work_left: Vec<u32>,
+}
+
+impl Summary for Workers {
+    fn summary(&self) -> String {
+        let in_progress: String = self
+            .in_progress
+            .iter()
+            .map(|&step| (step + b'A') as char)
+            .collect();
+        let steps_left = self.work_left.iter().filter(|&&work| work > 0).count();
+        format!(
+            "second {}, working on {:?}, {} steps left",
+            self.second, in_progress, steps_left
+        )
+    }
 }
 
 #[aoc_generator(day7)]
-pub fn generator(input: &str) -> Vec<Instruction> {
-    input
-        .lines()
-        .map(|l| {
-            let depends_on = l.as_bytes()[PREFIX1] - b'A';
-            let step = l.as_bytes()[PREFIX2] - b'A';
-            Instruction { step, depends_on }
+pub fn generator(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    let input = &input::normalize(input);
+    error::lines(7, input)
+        .map(|line| {
+            // parse a line like: "Step C must be finished before step A can begin."
+            let mut cursor = Cursor::new(line);
+            cursor.literal("Step ")?;
+            let depends_on = parse_step(&mut cursor)?;
+            cursor.literal(" must be finished before step ")?;
+            let step = parse_step(&mut cursor)?;
+            cursor.literal(" can begin.")?;
+            cursor.end()?;
+            Ok(Instruction { step, depends_on })
         })
         .collect()
 }
 
+/// Parse a single step letter and return it as index starting at 0
+fn parse_step(cursor: &mut Cursor) -> Result<u8, ParseError> {
+    let c = cursor.char_if("a step between 'A' and 'Z'", |c| c.is_ascii_uppercase())?;
+    Ok(c as u8 - b'A')
+}
+
+/// Error for steps which can never begin, because they wait on each other
+fn cyclic_dependencies() -> NoAnswer {
+    NoAnswer::new(7, "the steps depend on each other in a cycle")
+}
+
 #[aoc(day7, part1)]
-pub fn solve_part1(input: &[Instruction]) -> String {
+pub fn solve_part1(input: &[Instruction]) -> Result<String, NoAnswer> {
     let mut highest_step = Max::new();
 
     let mut res = String::with_capacity(26);
@@ -44,22 +110,39 @@ pub fn solve_part1(input: &[Instruction]) -> String {
                 {
                     // either no deps or all done
                     step_is_done[i as usize] = true;
-                    res.push((i as u8 + b'A') as char);
+                    res.push((i + b'A') as char);
                     continue 'outer;
                 }
             }
         }
+        // No step is ready, although some are not done yet
+        return Err(cyclic_dependencies());
     }
 
-    res
+    Ok(res)
 }
 
 #[aoc(day7, part2)]
-pub fn solve_part2(input: &[Instruction]) -> usize {
-    solve_part2_impl(input, 15, 60)
+pub fn solve_part2(input: &[Instruction]) -> Result<usize, NoAnswer> {
+    solve_part2_with(input, &Params::default())
+}
+
+/// Time it takes to finish all steps with the given number of workers
+pub fn solve_part2_with(input: &[Instruction], params: &Params) -> Result<usize, NoAnswer> {
+    solve_part2_observed(input, params, &mut Silent)
 }
 
-fn solve_part2_impl(input: &[Instruction], workers: u8, base_step_cost: u8) -> usize {
+/// Like `solve_part2_with`, but report the `Workers` to `observer` every few seconds
+pub fn solve_part2_observed<O: Observer<Workers>>(
+    input: &[Instruction],
+    params: &Params,
+    observer: &mut O,
+) -> Result<usize, NoAnswer> {
+    let interval = observer.interval().max(1);
+    let Params {
+        workers,
+        base_step_cost,
+    } = *params;
     let mut highest_step = Max::new();
 
     let mut depends_on: Vec<Vec<u8>> = vec![vec![]; 26];
@@ -70,41 +153,22 @@ fn solve_part2_impl(input: &[Instruction], workers: u8, base_step_cost: u8) -> u
         highest_step.update(inst.depends_on);
         depends_on[inst.step as usize].push(inst.depends_on)
     }
-    let mut step_work_units_left: Vec<u8> = (0..=highest_step.get_max_extreme() as usize)
-        .map(|i| i as u8 + 1 + base_step_cost)
+    let mut step_work_units_left: Vec<u32> = (0..=u32::from(highest_step.get_max_extreme()))
+        .map(|i| i + 1 + u32::from(base_step_cost))
         .collect();
 
     let mut time_spent = 0;
 
     // This work counts the time spent
     while step_work_units_left.iter().any(|work_left| *work_left > 0) {
-        // println!(
-        //     "{:?}",
-        //     steps_under_work
-        //         .iter()
-        //         .map(|&x| (x + b'A') as char)
-        //         .collect::<String>()
-        // );
-        // println!(
-        //     "{:?}",
-        //     step_work_units_left
-        //         .iter()
-        //         .enumerate()
-        //         .map(|(i, &work)| format!("{}: {}", (i as u8 + b'A') as char, work))
-        //         .collect::<Vec<String>>()
-        // );
-
         time_spent += 1;
 
         // for each unit of work currently worked on, decrease the amount of work units left
         // Only keep work units with work left
-        steps_under_work = steps_under_work
-            .into_iter()
-            .filter(|step| {
-                step_work_units_left[*step as usize] -= 1;
-                step_work_units_left[*step as usize] > 0
-            })
-            .collect();
+        steps_under_work.retain(|step| {
+            step_work_units_left[*step as usize] -= 1;
+            step_work_units_left[*step as usize] > 0
+        });
 
         'search_for_work: for i in 0..=highest_step.get_max_extreme() {
             // we only need to search for work until this queue is full
@@ -126,9 +190,28 @@ fn solve_part2_impl(input: &[Instruction], workers: u8, base_step_cost: u8) -> u
                 }
             }
         }
+        // Nobody works, but there are steps left
+        if steps_under_work.is_empty() && step_work_units_left.iter().any(|&work| work > 0) {
+            return Err(cyclic_dependencies());
+        }
+
+        // The work was distributed for the second `time_spent - 1`
+        let second = time_spent - 1;
+        if second % interval == 0 {
+            let mut in_progress = steps_under_work.clone();
+            in_progress.sort();
+            observer.observe(
+                second,
+                &Workers {
+                    second,
+                    in_progress,
+                    work_left: step_work_units_left.clone(),
+                },
+            );
+        }
     }
 
-    time_spent - 1
+    Ok(time_spent - 1)
 }
 
 #[cfg(test)]
@@ -143,14 +226,90 @@ Step F must be finished before step E can begin.
 
 #[test]
 fn test_part_1() {
-    let processed = generator(TEST_INPUT);
+    let processed = generator(TEST_INPUT).unwrap();
     let res = solve_part1(&processed);
-    assert_eq!(res, "CABDFE")
+    assert_eq!(res, Ok("CABDFE".to_string()))
+}
+
+#[test]
+fn test_untidy_input() {
+    let processed = generator(&input::untidy(TEST_INPUT)).unwrap();
+    assert_eq!(solve_part1(&processed), Ok("CABDFE".to_string()))
 }
 
 #[test]
-fn test_part_2_impl() {
-    let processed = generator(TEST_INPUT);
-    let res = solve_part2_impl(&processed, 2, 0);
-    assert_eq!(res, 15)
+fn test_part_2_with() {
+    let processed = generator(TEST_INPUT).unwrap();
+    let params = Params {
+        workers: 2,
+        base_step_cost: 0,
+    };
+    let res = solve_part2_with(&processed, &params);
+    assert_eq!(res, Ok(15))
+}
+
+#[test]
+fn test_part_2_observed() {
+    let processed = generator(TEST_INPUT).unwrap();
+    let params = Params {
+        workers: 2,
+        base_step_cost: 0,
+    };
+    let mut seconds = Vec::new();
+    let res = solve_part2_observed(&processed, &params, &mut |second, workers: &Workers| {
+        seconds.push((second, workers.summary()))
+    });
+    assert_eq!(res, Ok(15));
+    assert_eq!(seconds.len(), 16);
+    assert_eq!(
+        seconds[0],
+        (0, "second 0, working on \"C\", 6 steps left".to_string())
+    );
+    assert_eq!(
+        seconds[4],
+        (4, "second 4, working on \"BF\", 4 steps left".to_string())
+    );
+    assert_eq!(
+        seconds[15],
+        (15, "second 15, working on \"\", 0 steps left".to_string())
+    );
+}
+
+#[test]
+fn test_generator_error() {
+    let err = generator("Step C must be finished before step a can begin.").unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::new(7, 1, 37, "a step between 'A' and 'Z'", "\"a\"")
+    );
+}
+
+#[test]
+fn test_cyclic_dependencies() {
+    let processed = generator(
+        "Step A must be finished before step B can begin.\n\
+         Step B must be finished before step A can begin.",
+    )
+    .unwrap();
+    let err = NoAnswer::new(7, "the steps depend on each other in a cycle");
+    assert_eq!(solve_part1(&processed), Err(err.clone()));
+    assert_eq!(solve_part2(&processed), Err(err));
+}
+
+#[test]
+fn test_params() {
+    let mut config = ::params::Config::new();
+    config.set(7, "workers", "0");
+    assert_eq!(
+        config.params::<Params>(7).unwrap_err().to_string(),
+        "day 7: invalid value \"0\" for `workers`: at least one worker is needed"
+    );
+
+    let processed = generator(TEST_INPUT).unwrap();
+    let params = Params {
+        workers: 1,
+        base_step_cost: 255,
+    };
+    // Each step takes longer than fits into an `u8`
+    assert_eq!(solve_part2_with(&processed, &params), Ok(6 * 255 + 21));
 }
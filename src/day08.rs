@@ -1,24 +1,57 @@
+//! Day 8: Memory Maneuver
+
+use error::{self, ParseError};
+use input;
+use parse::Cursor;
+
+/// Parse the license file into the tree of nodes
 #[aoc_generator(day8)]
-pub fn generator(input: &str) -> Vec<usize> {
-    input
-        .lines()
-        .flat_map(|l| l.split(' ').map(|x| x.parse().unwrap()))
-        .collect()
+pub fn generator(input: &str) -> Result<Node, ParseError> {
+    let input = &input::normalize(input);
+    // Keep the line with each number, such that errors can point to it
+    let mut numbers = Vec::new();
+    let mut last_line = error::Line::new(8, 1, "");
+    for line in error::lines(8, input) {
+        last_line = line;
+        let mut cursor = Cursor::new(line);
+        cursor.skip_whitespace();
+        if cursor.is_end() {
+            continue;
+        }
+        numbers.extend(cursor.separated(" ", |cursor| {
+            let rest = cursor.rest();
+            let value = cursor.integer("a number")?;
+            let token = &rest[..rest.len() - cursor.rest().len()];
+            Ok((line, token, value))
+        })?);
+        cursor.end()?;
+    }
+
+    let mut iter = numbers.iter().cloned();
+    let root = parse_node(&mut iter, &last_line)?;
+    if let Some((line, token, _)) = iter.next() {
+        return Err(line.error_at(token, "end of input"));
+    }
+    Ok(root)
 }
 
-#[derive(Debug)]
-struct Node {
-    children: Vec<Node>,
-    metadata: Vec<usize>,
+/// A node of the license tree
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Node {
+    pub children: Vec<Node>,
+    pub metadata: Vec<usize>,
 }
 
 impl Node {
-    fn sum(&self) -> usize {
+    /// Sum of the metadata entries of this node and all its descendants
+    pub fn sum(&self) -> usize {
         self.metadata.iter().sum::<usize>()
             + self.children.iter().map(|child| child.sum()).sum::<usize>()
     }
 
-    fn value(&self) -> usize {
+    /// Value of the node, as defined in part 2
+    pub fn value(&self) -> usize {
         // The value of a node depends on whether it has child nodes.
 
         // If a node has **no child nodes**, its value is the sum of its metadata entries. So, the value of
@@ -39,7 +72,7 @@ impl Node {
                 .map(|&idx| {
                     if idx == 0 {
                         0
-                    } else if let Some(child) = self.children.get(idx-1) {
+                    } else if let Some(child) = self.children.get(idx - 1) {
                         child.value()
                     } else {
                         0
@@ -51,30 +84,45 @@ impl Node {
 }
 
 #[aoc(day8, part1)]
-pub fn solve_part1(input: &[usize]) -> usize {
-    // Each node is specified by two values, first the number of child nodes, then the number of metadata entries
-    let root = parse_node(&mut input.iter().cloned());
+pub fn solve_part1(root: &Node) -> usize {
     root.sum()
 }
 
 #[aoc(day8, part2)]
-pub fn solve_part2(input: &[usize]) -> usize {
-    // Each node is specified by two values, first the number of child nodes, then the number of metadata entries
-    let root = parse_node(&mut input.iter().cloned());
+pub fn solve_part2(root: &Node) -> usize {
     root.value()
 }
 
-fn parse_node<I>(input: &mut I) -> Node
+fn parse_node<'a, I>(input: &mut I, last_line: &error::Line) -> Result<Node, ParseError>
 where
-    I: Iterator<Item = usize>,
+    I: Iterator<Item = (error::Line<'a>, &'a str, usize)>,
 {
-    let child_count = input.next().unwrap();
-    let metadata_count = input.next().unwrap();
+    // Each node is specified by two values, first the number of child nodes, then the number of metadata entries
+    let child_count = next_number(input, last_line, "the number of child nodes")?;
+    let metadata_count = next_number(input, last_line, "the number of metadata entries")?;
+
+    let children: Vec<Node> = (0..child_count)
+        .map(|_| parse_node(input, last_line))
+        .collect::<Result<_, _>>()?;
+    let metadata: Vec<usize> = (0..metadata_count)
+        .map(|_| next_number(input, last_line, "a metadata entry"))
+        .collect::<Result<_, _>>()?;
 
-    let children: Vec<Node> = (0..child_count).map(|_| parse_node(input)).collect();
-    let metadata: Vec<usize> = input.take(metadata_count as usize).collect();
+    Ok(Node { children, metadata })
+}
 
-    Node { children, metadata }
+fn next_number<'a, I>(
+    input: &mut I,
+    last_line: &error::Line,
+    expected: &str,
+) -> Result<usize, ParseError>
+where
+    I: Iterator<Item = (error::Line<'a>, &'a str, usize)>,
+{
+    input
+        .next()
+        .map(|(_, _, value)| value)
+        .ok_or_else(|| last_line.error_at_end(expected))
 }
 
 #[cfg(test)]
@@ -83,14 +131,37 @@ const TEST_INPUT: &str = r#"2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2
 
 #[test]
 fn test_part_1() {
-    let processed = generator(TEST_INPUT);
+    let processed = generator(TEST_INPUT).unwrap();
     let res = solve_part1(&processed);
     assert_eq!(res, 138)
 }
 
+#[test]
+fn test_untidy_input() {
+    let processed = generator(&input::untidy(TEST_INPUT)).unwrap();
+    assert_eq!(solve_part1(&processed), 138)
+}
+
 #[test]
 fn test_part_2() {
-    let processed = generator(TEST_INPUT);
+    let processed = generator(TEST_INPUT).unwrap();
     let res = solve_part2(&processed);
     assert_eq!(res, 66)
 }
+
+#[test]
+fn test_generator_error() {
+    let err = generator("2 3 0 3 10 11 12 1 1 0 1 99 2").unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::new(8, 1, 30, "a metadata entry", "end of line")
+    );
+}
+
+#[test]
+fn test_generator_invalid_number() {
+    assert_eq!(
+        generator("0 1\n2 3 x").unwrap_err(),
+        ParseError::new(8, 2, 5, "a number", "\"x\"")
+    );
+}
@@ -1,81 +1,258 @@
+//! Day 9: Marble Mania
+
+use budget::Budget;
+use error::{self, ParseError};
+use input;
 use intrusive_collections::{linked_list::CursorMut, LinkedList, LinkedListLink};
+use observer::{Observer, Silent, Summary};
+use parse::Cursor;
+use simulation::Simulation;
+
+params! {
+    /// Parameters of the marble game
+    pub struct Params {
+        /// Number of players, 0 to take it from the input
+        players: usize = 0,
+        /// Value of the last marble in part 1, 0 to take it from the input
+        last_marble: usize = 0,
+        /// Part 2 plays until the last marble is this many times larger
+        part2_multiplier: usize = 100,
+    }
+
+    validate(params) {
+        if params.part2_multiplier == 0 {
+            return Err(::params::Invalid::new(
+                "part2_multiplier",
+                "at least one marble must be played",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The rules of a game, as given by the puzzle input
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Rules {
+    pub players: usize,
+    pub last_marble: usize,
+}
+
+impl Rules {
+    /// The rules with the nonzero `params` taking precedence
+    pub fn with_params(&self, params: &Params) -> Self {
+        let or_input = |param, input| if param == 0 { input } else { param };
+        Rules {
+            players: or_input(params.players, self.players),
+            last_marble: or_input(params.last_marble, self.last_marble),
+        }
+    }
+}
+
+#[aoc_generator(day9)]
+pub fn generator(input: &str) -> Result<Rules, ParseError> {
+    let input = &input::normalize(input);
+    let mut lines = error::lines(9, input);
+    let line = lines.next().unwrap_or_else(|| error::Line::new(9, 1, ""));
+
+    // parse a line like: "476 players; last marble is worth 71431 points"
+    let mut cursor = Cursor::new(line);
+    let players = parse_positive(&mut cursor, "a number of players")?;
+    cursor.literal(" players; last marble is worth ")?;
+    let last_marble = parse_positive(&mut cursor, "a marble value")?;
+    cursor.literal(" points")?;
+    cursor.end()?;
+    if let Some(line) = lines.next() {
+        return Err(line.error_at(line.text(), "end of input"));
+    }
+    Ok(Rules {
+        players,
+        last_marble,
+    })
+}
+
+/// Parse an integer, which must not be 0
+fn parse_positive(cursor: &mut Cursor, expected: &str) -> Result<usize, ParseError> {
+    let rest = cursor.rest();
+    let value = cursor.integer(expected)?;
+    if value == 0 {
+        let token = &rest[..rest.len() - cursor.rest().len()];
+        return Err(cursor.line().error_at(token, expected));
+    }
+    Ok(value)
+}
 
 #[aoc(day9, part1, vector)]
-pub fn solve_part1(_input: &str) -> usize {
-    solve_part1_impl(476, 71431)
+pub fn solve_part1(input: &Rules) -> usize {
+    solve_part1_with(input, &Params::default())
+}
+
+pub fn solve_part1_with(input: &Rules, params: &Params) -> usize {
+    solve_part1_observed(input, params, &mut Silent)
+}
+
+/// Like `solve_part1_with`, but report the game to `observer` every few marbles
+pub fn solve_part1_observed<O: Observer<MarbleGame>>(
+    input: &Rules,
+    params: &Params,
+    observer: &mut O,
+) -> usize {
+    let rules = input.with_params(params);
+    solve_part1_impl_observed(rules.players, rules.last_marble, observer)
 }
 
 #[aoc(day9, part1, linked_list)]
-pub fn solve_part1_linked_list(_input: &str) -> usize {
-    solve_part1_impl_linked_list(476, 71431)
+pub fn solve_part1_linked_list(input: &Rules) -> usize {
+    solve_part1_linked_list_with(input, &Params::default())
+}
+
+pub fn solve_part1_linked_list_with(input: &Rules, params: &Params) -> usize {
+    solve_part1_linked_list_observed(input, params, &mut Silent)
+}
+
+/// Like `solve_part1_linked_list_with`, but report the `Progress` every few marbles
+pub fn solve_part1_linked_list_observed<O: Observer<Progress>>(
+    input: &Rules,
+    params: &Params,
+    observer: &mut O,
+) -> usize {
+    let rules = input.with_params(params);
+    solve_part1_impl_linked_list_observed(rules.players, rules.last_marble, observer)
 }
 
 #[aoc(day9, part2)]
-pub fn solve_part2(_input: &str) -> usize {
-    solve_part1_impl_linked_list(476, 71431 * 100)
+pub fn solve_part2(input: &Rules) -> usize {
+    solve_part2_with(input, &Params::default())
 }
 
-fn solve_part1_impl(players: usize, highest_marble_value: usize) -> usize {
-    let mut marble_ring = Vec::with_capacity(highest_marble_value + 1);
-    let mut current_marble: usize;
-    let mut current_player: usize;
-    let mut points_per_player: Vec<usize> = vec![0; players];
+pub fn solve_part2_with(input: &Rules, params: &Params) -> usize {
+    solve_part2_observed(input, params, &mut Silent)
+}
 
-    let step_player = |player: usize| -> usize { (player + 1) % players };
+/// Like `solve_part2_with`, but report the `Progress` every few marbles
+pub fn solve_part2_observed<O: Observer<Progress>>(
+    input: &Rules,
+    params: &Params,
+    observer: &mut O,
+) -> usize {
+    let rules = input.with_params(params);
+    solve_part1_impl_linked_list_observed(
+        rules.players,
+        rules.last_marble * params.part2_multiplier,
+        observer,
+    )
+}
 
-    // init the board to a reasonable initial size
-    marble_ring.push(0);
-    marble_ring.push(2);
-    marble_ring.push(1);
-    current_player = 1;
-    current_marble = 1;
+/// State of the marble game, storing the ring in a `Vec`
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MarbleGame {
+    /// The marbles in clockwise order
+    pub ring: Vec<usize>,
+    /// Index of the current marble in `ring`
+    pub current_marble: usize,
+    pub current_player: usize,
+    pub points_per_player: Vec<usize>,
+    /// Value of the marble placed in the next step
+    pub next_marble: usize,
+    pub highest_marble_value: usize,
+}
 
-    for value in 3..=highest_marble_value {
-        // eprintln!(
-        //     "[{}] {}",
-        //     current_player + 1,
-        //     marble_ring
-        //         .iter()
-        //         .enumerate()
-        //         .map(|(i, value)| format!(
-        //             "{:>2}{}",
-        //             value,
-        //             if i == current_marble { '*' } else { ' ' },
-        //         ))
-        //         .collect::<String>()
-        // );
+impl MarbleGame {
+    pub fn new(players: usize, highest_marble_value: usize) -> Self {
+        let mut ring = Vec::with_capacity(highest_marble_value + 1);
+        // init the board to a reasonable initial size
+        ring.push(0);
+        ring.push(2);
+        ring.push(1);
+        MarbleGame {
+            ring,
+            current_marble: 1,
+            current_player: 1,
+            points_per_player: vec![0; players],
+            next_marble: 3,
+            highest_marble_value,
+        }
+    }
 
-        current_player = step_player(current_player);
-        if value % 23 != 0 {
-            current_marble = (current_marble + 2) % marble_ring.len();
-            marble_ring.insert(current_marble, value);
+    pub fn high_score(&self) -> usize {
+        self.points_per_player.iter().cloned().max().unwrap()
+    }
+}
+
+impl Summary for MarbleGame {
+    fn summary(&self) -> String {
+        format!(
+            "marble {} of {}, high score {}",
+            self.next_marble - 1,
+            self.highest_marble_value,
+            self.high_score()
+        )
+    }
+}
+
+impl Simulation for MarbleGame {
+    fn step(&mut self) {
+        let value = self.next_marble;
+        self.next_marble += 1;
+        self.current_player = (self.current_player + 1) % self.points_per_player.len();
+        if !value.is_multiple_of(23) {
+            self.current_marble = (self.current_marble + 2) % self.ring.len();
+            self.ring.insert(self.current_marble, value);
         } else {
             // Add the extra length to make sure it never underflows
-            current_marble = (current_marble + marble_ring.len() - 7) % marble_ring.len();
-            points_per_player[current_player] += value;
-            points_per_player[current_player] += marble_ring.remove(current_marble);
+            self.current_marble = (self.current_marble + self.ring.len() - 7) % self.ring.len();
+            self.points_per_player[self.current_player] += value;
+            self.points_per_player[self.current_player] += self.ring.remove(self.current_marble);
         }
     }
 
-    // eprintln!(
-    //     "[{}] {}",
-    //     current_player + 1,
-    //     marble_ring
-    //         .iter()
-    //         .enumerate()
-    //         .map(|(i, value)| format!(
-    //             "{:>2}{}",
-    //             value,
-    //             if i == current_marble { '*' } else { ' ' },
-    //         ))
-    //         .collect::<String>()
-    // );
-
-    // for (player, points) in points_per_player.iter().enumerate() {
-    //     println!("Player {:>2}: {}", player + 1, points);
-    // }
+    fn is_finished(&self) -> bool {
+        self.next_marble > self.highest_marble_value
+    }
+}
 
-    points_per_player.into_iter().max().unwrap()
+/// Highest score after playing until `highest_marble_value`, storing the ring in a `Vec`
+pub fn solve_part1_impl(players: usize, highest_marble_value: usize) -> usize {
+    solve_part1_impl_observed(players, highest_marble_value, &mut Silent)
+}
+
+/// Like `solve_part1_impl`, but report the game to `observer` every few marbles
+pub fn solve_part1_impl_observed<O: Observer<MarbleGame>>(
+    players: usize,
+    highest_marble_value: usize,
+    observer: &mut O,
+) -> usize {
+    let mut game = MarbleGame::new(players, highest_marble_value);
+    game.run_until_observed(&Budget::unlimited(), MarbleGame::is_finished, observer)
+        .expect("An unlimited budget is never exceeded");
+    game.high_score()
+}
+
+/// Progress of the linked list game, which has no state to share with an observer
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Progress {
+    /// Value of the marble placed last
+    pub marble: usize,
+    pub highest_marble_value: usize,
+    pub high_score: usize,
+}
+
+impl Progress {
+    fn new(marble: usize, highest_marble_value: usize, points_per_player: &[usize]) -> Self {
+        Progress {
+            marble,
+            highest_marble_value,
+            high_score: points_per_player.iter().cloned().max().unwrap(),
+        }
+    }
+}
+
+impl Summary for Progress {
+    fn summary(&self) -> String {
+        format!(
+            "marble {} of {}, high score {}",
+            self.marble, self.highest_marble_value, self.high_score
+        )
+    }
 }
 
 // A simple struct containing an instrusive link and a value
@@ -97,7 +274,22 @@ impl Node {
 // collection. This is automatically generated using a macro.
 intrusive_adapter!(NodeAdapter = Box<Node>: Node { link: LinkedListLink });
 
-fn solve_part1_impl_linked_list(players: usize, highest_marble_value: usize) -> usize {
+/// Highest score after playing until `highest_marble_value`, storing the ring in a linked list
+///
+/// Unlike `solve_part1_impl`, this is fast enough for part 2.
+pub fn solve_part1_impl_linked_list(players: usize, highest_marble_value: usize) -> usize {
+    solve_part1_impl_linked_list_observed(players, highest_marble_value, &mut Silent)
+}
+
+/// Like `solve_part1_impl_linked_list`, but report the `Progress` every few marbles
+///
+/// The steps and reports are the same as for `MarbleGame`.
+pub fn solve_part1_impl_linked_list_observed<O: Observer<Progress>>(
+    players: usize,
+    highest_marble_value: usize,
+    observer: &mut O,
+) -> usize {
+    let interval = observer.interval().max(1);
     let mut marble_ring = LinkedList::new(NodeAdapter::new());
     let mut current_player: usize;
     let mut points_per_player: Vec<usize> = vec![0; players];
@@ -114,8 +306,13 @@ fn solve_part1_impl_linked_list(players: usize, highest_marble_value: usize) ->
     current_marble.move_next();
 
     for value in 3..=highest_marble_value {
+        let steps = value - 3;
+        if steps % interval == 0 {
+            let progress = Progress::new(value - 1, highest_marble_value, &points_per_player);
+            observer.observe(steps, &progress);
+        }
         current_player = step_player(current_player);
-        if value % 23 != 0 {
+        if !value.is_multiple_of(23) {
             move_forward(&mut current_marble, 1);
             current_marble.insert_after(Node::new(value));
             // move onto the newly inserted value
@@ -131,6 +328,13 @@ fn solve_part1_impl_linked_list(players: usize, highest_marble_value: usize) ->
         }
     }
 
+    // Like `run_until_observed`, the finished game is reported as well
+    let steps = highest_marble_value.max(2) - 2;
+    if steps.is_multiple_of(interval) {
+        let progress = Progress::new(steps + 2, highest_marble_value, &points_per_player);
+        observer.observe(steps, &progress);
+    }
+
     points_per_player.into_iter().max().unwrap()
 }
 
@@ -195,3 +399,76 @@ fn test_part_1_f() {
     let res = solve_part1_impl(30, 5807);
     assert_eq!(res, 37305)
 }
+
+#[test]
+fn test_part_1_observed() {
+    let mut vector = Vec::new();
+    let res = solve_part1_impl_observed(10, 1618, &mut |steps, game: &MarbleGame| {
+        vector.push((steps, game.summary()))
+    });
+    assert_eq!(res, 8317);
+    let mut linked_list = Vec::new();
+    let res = solve_part1_impl_linked_list_observed(10, 1618, &mut |steps, progress: &Progress| {
+        linked_list.push((steps, progress.summary()))
+    });
+    assert_eq!(res, 8317);
+
+    assert_eq!(vector, linked_list);
+    assert_eq!(vector.len(), 1617);
+    assert_eq!(vector[0], (0, "marble 2 of 1618, high score 0".to_string()));
+    assert_eq!(
+        vector[1616],
+        (1616, "marble 1618 of 1618, high score 8317".to_string())
+    );
+}
+
+#[test]
+fn test_generator() {
+    let rules = generator("10 players; last marble is worth 1618 points").unwrap();
+    assert_eq!(solve_part1(&rules), 8317);
+    assert_eq!(solve_part1_linked_list(&rules), 8317);
+    let rules = generator(&input::untidy(
+        "13 players; last marble is worth 7999 points\n",
+    ))
+    .unwrap();
+    assert_eq!(solve_part1(&rules), 146_373);
+}
+
+#[test]
+fn test_generator_error() {
+    assert_eq!(
+        generator("10 players; last marble is worth 1618").unwrap_err(),
+        ParseError::new(9, 1, 38, "\" points\"", "end of line")
+    );
+    assert_eq!(
+        generator("0 players; last marble is worth 1618 points").unwrap_err(),
+        ParseError::new(9, 1, 1, "a number of players", "\"0\"")
+    );
+    assert_eq!(
+        generator("").unwrap_err(),
+        ParseError::new(9, 1, 1, "a number of players", "end of line")
+    );
+}
+
+#[test]
+fn test_params() {
+    let mut config = ::params::Config::new();
+    config.set(9, "part2_multiplier", "0");
+    assert_eq!(
+        config.params::<Params>(9).unwrap_err().to_string(),
+        "day 9: invalid value \"0\" for `part2_multiplier`: at least one marble must be played"
+    );
+    // Only the parameters, which are set, override the input
+    let rules = generator("10 players; last marble is worth 1618 points").unwrap();
+    let mut config = ::params::Config::new();
+    config.set(9, "last_marble", "25");
+    let params = config.params(9).unwrap();
+    assert_eq!(
+        rules.with_params(&params),
+        Rules {
+            players: 10,
+            last_marble: 25
+        }
+    );
+    assert_eq!(solve_part1_with(&rules, &params), 32);
+}
@@ -1,103 +1,123 @@
-use misc_utils::{Max, Min};
+//! Day 10: The Stars Align
+
+use budget::{Budget, BudgetExceeded};
+use error::{self, ParseError};
+use geometry::{self, BoundingBox, Vector};
+use grid::Grid;
+use input;
+use parse::Cursor;
+use simulation::Simulation;
+
+params! {
+    /// Limits for waiting on the message
+    pub struct Params {
+        /// Maximal number of seconds to simulate, 0 for no limit
+        max_steps: u64 = 1_000_000,
+        /// Maximal time to search in milliseconds, 0 for no limit
+        timeout_ms: u64 = 0,
+    }
+}
 
 #[aoc_generator(day10)]
-pub fn generator(input: &str) -> Vec<Point> {
-    let offset1 = "position=<".len();
-    let offset2 = "position=<     7,      0> velocity=<".len();
-
-    input
-        .lines()
-        .map(|l| {
-            let mut coords = l[offset1..offset1 + 14]
-                .split(", ")
-                .map(|part| part.trim().parse::<isize>().unwrap());
-            let x = coords.next().unwrap();
-            let y = coords.next().unwrap();
-            let mut dir = l[offset2..offset2 + 6]
-                .split(", ")
-                .map(|part| part.trim().parse::<isize>().unwrap());
-            let delta_x = dir.next().unwrap();
-            let delta_y = dir.next().unwrap();
-            Point {
-                position: Coordinate { x, y },
-                velocity: Direction { delta_x, delta_y },
-            }
+pub fn generator(input: &str) -> Result<Vec<Point>, ParseError> {
+    let input = &input::normalize(input);
+    error::lines(10, input)
+        .map(|line| {
+            // parse a line like: "position=< 9,  1> velocity=< 0,  2>"
+            let mut cursor = Cursor::new(line);
+            cursor.literal("position=<")?;
+            let (x, y) = parse_pair(&mut cursor)?;
+            cursor.literal(">")?;
+            cursor.skip_whitespace();
+            cursor.literal("velocity=<")?;
+            let (delta_x, delta_y) = parse_pair(&mut cursor)?;
+            cursor.literal(">")?;
+            cursor.end()?;
+            Ok(Point {
+                position: geometry::Point::new(x, y),
+                velocity: Vector::new(delta_x, delta_y),
+            })
         })
         .collect()
 }
 
+/// Parse two comma separated numbers, which can be padded with spaces
+fn parse_pair(cursor: &mut Cursor) -> Result<(isize, isize), ParseError> {
+    cursor.skip_whitespace();
+    let a = cursor.integer("a number")?;
+    cursor.skip_whitespace();
+    cursor.literal(",")?;
+    cursor.skip_whitespace();
+    let b = cursor.integer("a number")?;
+    cursor.skip_whitespace();
+    Ok((a, b))
+}
+
+/// A point of light moving across the sky
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Point {
-    position: Coordinate,
-    velocity: Direction,
+    pub position: geometry::Point<isize>,
+    /// Movement per second
+    pub velocity: Vector<isize>,
 }
 
 impl Point {
-    fn step(&mut self) {
-        self.position.move_coordinate(&self.velocity);
+    /// Move the point by one second
+    pub fn step(&mut self) {
+        self.position += self.velocity;
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Coordinate {
-    x: isize,
-    y: isize,
+/// All points of light, each step is one second
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Sky {
+    pub points: Vec<Point>,
 }
 
-impl Coordinate {
-    fn move_coordinate(&mut self, direction: &Direction) {
-        self.x += direction.delta_x;
-        self.y += direction.delta_y;
+impl Simulation for Sky {
+    fn step(&mut self) {
+        self.points.iter_mut().for_each(Point::step);
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Direction {
-    delta_x: isize,
-    delta_y: isize,
+#[aoc(day10, part1)]
+pub fn solve_part1(input: &[Point]) -> Result<String, BudgetExceeded> {
+    solve_part1_with(input, &Params::default())
 }
 
-#[aoc(day10, part1)]
-pub fn solve_part1(input: &[Point]) -> String {
-    let mut input = input.to_vec();
-    for seconds in 0.. {
-        if let Some(res) = print_current_step(&input) {
-            return format!(
-                "\n{}\n\nIt would take {} seconds for the message to appear",
-                res, seconds
-            );
-        }
-        input.iter_mut().for_each(|p| p.step());
-    }
-    unreachable!()
+pub fn solve_part1_with(input: &[Point], params: &Params) -> Result<String, BudgetExceeded> {
+    let mut sky = Sky {
+        points: input.to_vec(),
+    };
+    let budget = Budget::from_limits(params.max_steps, params.timeout_ms);
+    let seconds = sky
+        .run_until_within(&budget, |sky| print_current_step(&sky.points).is_some())?
+        .expect("The sky never stops moving");
+    Ok(format!(
+        "\n{}\n\nIt would take {} seconds for the message to appear",
+        print_current_step(&sky.points).unwrap(),
+        seconds
+    ))
 }
 
-fn print_current_step(data: &[Point]) -> Option<String> {
-    let mut min_x = Min::new();
-    let mut max_x = Max::new();
-    let mut min_y = Min::new();
-    let mut max_y = Max::new();
-    for p in data {
-        min_x.update(p.position.x);
-        max_x.update(p.position.x);
-        min_y.update(p.position.y);
-        max_y.update(p.position.y);
-    }
-    let (min_x, max_x, min_y, max_y) = (
-        min_x.get_min_extreme(),
-        max_x.get_max_extreme(),
-        min_y.get_min_extreme(),
-        max_y.get_max_extreme(),
-    );
+/// Render the points, if they are close enough to each other to form a message
+pub fn print_current_step(data: &[Point]) -> Option<String> {
+    let bbox = BoundingBox::from_points(data.iter().map(|p| p.position))?;
 
-    if ((max_y - min_y + 1) as usize) < 20 {
-        let mut field: Vec<Vec<char>> =
-            vec![vec![' '; (max_x - min_x + 1) as usize]; (max_y - min_y + 1) as usize];
+    if (bbox.height() as usize) < 20 {
+        let mut field = Grid::with_origin(
+            bbox.min.x,
+            bbox.min.y,
+            bbox.width() as usize,
+            bbox.height() as usize,
+            false,
+        );
         for p in data {
-            field[(p.position.y - min_y) as usize][(p.position.x - min_x) as usize] = '#';
+            field[p.position] = true;
         }
-        let lines: Vec<String> = field.into_iter().map(|x| x.into_iter().collect()).collect();
-        Some((&*lines).join("\n").to_string())
+        Some(field.display(|&c| if c { '#' } else { ' ' }).to_string())
     } else {
         None
     }
@@ -139,6 +159,41 @@ position=<    -3,      6> velocity=< 2, -1>
 
 #[test]
 fn test_part_1() {
-    let processed = generator(TEST_INPUT);
-    solve_part1(&processed);
+    let processed = generator(TEST_INPUT).unwrap();
+    solve_part1(&processed).unwrap();
+}
+
+#[test]
+fn test_untidy_input() {
+    let processed = generator(&input::untidy(TEST_INPUT)).unwrap();
+    let expected = solve_part1(&generator(TEST_INPUT).unwrap());
+    assert_eq!(solve_part1(&processed), expected);
+}
+
+#[test]
+fn test_part_1_budget() {
+    // The points move apart and never form a message
+    let processed =
+        generator("position=<0, 0> velocity=<1, 0>\nposition=<0, 30> velocity=<-1, 0>").unwrap();
+    let params = Params {
+        max_steps: 10,
+        timeout_ms: 0,
+    };
+    assert_eq!(
+        solve_part1_with(&processed, &params),
+        Err(BudgetExceeded::Steps(10))
+    );
+}
+
+#[test]
+fn test_generator_wide_numbers() {
+    let points = generator("position=<123456,-7> velocity=<   -10, 3>").unwrap();
+    assert_eq!(points[0].position, geometry::Point::new(123_456, -7));
+    assert_eq!(points[0].velocity, Vector::new(-10, 3));
+}
+
+#[test]
+fn test_generator_error() {
+    let err = generator("position=< 9,  1> velocity=< 0;  2>").unwrap_err();
+    assert_eq!(err, ParseError::new(10, 1, 31, "\",\"", "\";\""));
 }
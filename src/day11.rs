@@ -1,28 +1,81 @@
+//! Day 11: Chronal Charge
+
+use error::{self, ParseError};
+use geometry::Point;
+use grid::Grid;
+use input;
+
+params! {
+    /// Parameters of the fuel grid
+    pub struct Params {
+        /// Width and height of the grid
+        grid_size: usize = 300,
+        /// Size of the square searched in part 1
+        area_size: usize = 3,
+        /// Largest square size tried in part 2
+        ///
+        /// The default is a heuristic, which misses a larger best square for about 1% of the
+        /// serial numbers. Use the grid size for an exhaustive search.
+        max_area_size: usize = 19,
+    }
+
+    validate(params) {
+        if params.area_size == 0 {
+            return Err(::params::Invalid::new("area_size", "the square must not be empty"));
+        }
+        if params.max_area_size < params.area_size {
+            return Err(::params::Invalid::new(
+                "max_area_size",
+                "must not be smaller than `area_size`",
+            ));
+        }
+        if params.grid_size < params.max_area_size {
+            return Err(::params::Invalid::new(
+                "grid_size",
+                "the squares must fit into the grid",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[aoc_generator(day11)]
+pub fn generator(input: &str) -> Result<isize, ParseError> {
+    let input = &input::normalize(input);
+    let line = error::lines(11, input)
+        .next()
+        .unwrap_or_else(|| error::Line::new(11, 1, ""));
+    line.parse(line.text().trim(), "a grid serial number")
+}
+
 #[aoc(day11, part1)]
-pub fn solve_part1(input: &str) -> String {
-    let grid_serial_number: isize = input.trim().parse().unwrap();
-    let grid = make_fuel_grid(grid_serial_number);
-    let ((x, y), _fuel) = find_largest_area(&grid, 3);
+pub fn solve_part1(grid_serial_number: &isize) -> String {
+    solve_part1_with(grid_serial_number, &Params::default())
+}
+
+pub fn solve_part1_with(grid_serial_number: &isize, params: &Params) -> String {
+    let grid = make_fuel_grid(*grid_serial_number, params.grid_size);
+    let ((x, y), _fuel) = find_largest_area(&grid, params.area_size);
     format!("{},{}", x, y)
 }
 
 #[aoc(day11, part2)]
-pub fn solve_part2(input: &str) -> String {
-    let grid_serial_number: isize = input.trim().parse().unwrap();
-    let grid = make_fuel_grid(grid_serial_number);
-    let ((x, y), area_size) = find_best_area(&grid);
+pub fn solve_part2(grid_serial_number: &isize) -> String {
+    solve_part2_with(grid_serial_number, &Params::default())
+}
+
+pub fn solve_part2_with(grid_serial_number: &isize, params: &Params) -> String {
+    let grid = make_fuel_grid(*grid_serial_number, params.grid_size);
+    let ((x, y), area_size) = find_best_area(&grid, params.max_area_size);
     format!("{},{},{}", x, y, area_size)
 }
 
-fn make_fuel_grid(grid_serial_number: isize) -> Vec<Vec<isize>> {
+/// Fuel levels of a `size` x `size` grid, whose coordinates start at 1
+pub fn make_fuel_grid(grid_serial_number: isize, size: usize) -> Grid<isize> {
     // The grid index starts with 1 for all calculations below
-    (1..=300)
-        .map(|x| -> Vec<isize> {
-            (1..=300)
-                .map(|y| get_fuel_level(grid_serial_number, x, y))
-                .collect()
-        })
-        .collect()
+    Grid::from_fn(1, 1, size, size, |pos| {
+        get_fuel_level(grid_serial_number, pos.x, pos.y)
+    })
 }
 
 /// Calculate the fuel level for each cell in the grid
@@ -46,7 +99,7 @@ fn make_fuel_grid(grid_serial_number: isize) -> Vec<Vec<isize>> {
 ///  * Subtracting 5 produces 9 - 5 = 4.
 ///
 /// So, the power level of this fuel cell is 4.
-fn get_fuel_level(grid_serial_number: isize, x: isize, y: isize) -> isize {
+pub fn get_fuel_level(grid_serial_number: isize, x: isize, y: isize) -> isize {
     let rack_id = x + 10;
     let mut power_level = rack_id * y;
     power_level += grid_serial_number;
@@ -61,19 +114,20 @@ fn get_hundreds(value: isize) -> isize {
 }
 
 /// This functions returns the x and y coordinates, as well as, the amount of fuel in that area
-fn find_largest_area(grid: &[Vec<isize>], area_size: usize) -> ((usize, usize), isize) {
-    // For an area of 3, we need to count x, x+1, x+2, therefore -1 on the max
-    let area_size = area_size - 1;
+pub fn find_largest_area(grid: &Grid<isize>, area_size: usize) -> ((usize, usize), isize) {
+    let area_size = area_size as isize;
     let mut current_coords = (0, 0);
-    let mut current_area_sum = 0;
-    for x in 0..(300 - area_size) {
-        for y in 0..(300 - area_size) {
-            let area_sum: isize = (x..=x + area_size)
-                .flat_map(|a| (y..=y + area_size).map(move |b| (a, b)))
-                .map(|(a, b)| grid[a][b])
+    // All areas might have a negative sum
+    let mut current_area_sum = isize::MIN;
+    // For an area of 3, we need to count x, x+1, x+2, therefore +1 on the max
+    for x in grid.min_x()..=(grid.max_x() - area_size + 1) {
+        for y in grid.min_y()..=(grid.max_y() - area_size + 1) {
+            let area_sum: isize = (x..x + area_size)
+                .flat_map(|a| (y..y + area_size).map(move |b| Point::new(a, b)))
+                .map(|pos| grid[pos])
                 .sum();
             if area_sum > current_area_sum {
-                current_coords = (x + 1, y + 1);
+                current_coords = (x as usize, y as usize);
                 current_area_sum = area_sum;
             }
         }
@@ -82,12 +136,12 @@ fn find_largest_area(grid: &[Vec<isize>], area_size: usize) -> ((usize, usize),
 }
 
 /// This functions returns the x and y coordinates, as well as, the size of the area
-fn find_best_area(grid: &[Vec<isize>]) -> ((usize, usize), usize) {
+pub fn find_best_area(grid: &Grid<isize>, max_area_size: usize) -> ((usize, usize), usize) {
     let mut current_coords = (0, 0);
-    let mut current_area_sum = 0;
+    let mut current_area_sum = isize::MIN;
     let mut current_area_size = 0;
-    for area_size in 1..20 {
-        let (coords, area_sum) = find_largest_area(&grid, area_size);
+    for area_size in 1..=max_area_size {
+        let (coords, area_sum) = find_largest_area(grid, area_size);
         if area_sum > current_area_sum {
             current_area_sum = area_sum;
             current_area_size = area_size;
@@ -100,19 +154,19 @@ fn find_best_area(grid: &[Vec<isize>]) -> ((usize, usize), usize) {
 #[test]
 fn test_make_fuel_grid_1() {
     // Fuel cell at  122,79, grid serial number 57: power level -5.
-    assert_eq!(make_fuel_grid(57)[122 - 1][79 - 1], -5);
+    assert_eq!(make_fuel_grid(57, 300)[Point::new(122, 79)], -5);
 }
 
 #[test]
 fn test_make_fuel_grid_2() {
     // Fuel cell at 217,196, grid serial number 39: power level  0.
-    assert_eq!(make_fuel_grid(39)[217 - 1][196 - 1], 0);
+    assert_eq!(make_fuel_grid(39, 300)[Point::new(217, 196)], 0);
 }
 
 #[test]
 fn test_make_fuel_grid_3() {
     // Fuel cell at 101,153, grid serial number 71: power level  4.
-    assert_eq!(make_fuel_grid(71)[101 - 1][153 - 1], 4);
+    assert_eq!(make_fuel_grid(71, 300)[Point::new(101, 153)], 4);
 }
 
 #[test]
@@ -123,27 +177,55 @@ fn test_get_fuel_level() {
 #[test]
 fn test_find_largest_area_1() {
     // For grid serial number 18, the largest total 3x3 square has a top-left corner of 33,45 (with a total power of 29)
-    let grid = make_fuel_grid(18);
+    let grid = make_fuel_grid(18, 300);
     assert_eq!(find_largest_area(&grid, 3), ((33, 45), 29));
 }
 
 #[test]
 fn test_find_largest_area_2() {
     // For grid serial number 42, the largest 3x3 square's top-left is 21,61 (with a total power of 30)
-    let grid = make_fuel_grid(42);
+    let grid = make_fuel_grid(42, 300);
     assert_eq!(find_largest_area(&grid, 3), ((21, 61), 30));
 }
 
 #[test]
 fn test_find_best_area_1() {
     // For grid serial number 18, the largest total square (with a total power of 113) is 16x16 and has a top-left corner of 90,269, so its identifier is 90,269,16.
-    let grid = make_fuel_grid(18);
-    assert_eq!(find_best_area(&grid), ((90, 269), 16));
+    let grid = make_fuel_grid(18, 300);
+    assert_eq!(find_best_area(&grid, 19), ((90, 269), 16));
 }
 
 #[test]
 fn test_find_best_area_2() {
     // For grid serial number 42, the largest total square (with a total power of 119) is 12x12 and has a top-left corner of 232,251, so its identifier is 232,251,12.
-    let grid = make_fuel_grid(42);
-    assert_eq!(find_best_area(&grid), ((232, 251), 12));
+    let grid = make_fuel_grid(42, 300);
+    assert_eq!(find_best_area(&grid, 19), ((232, 251), 12));
+}
+
+#[test]
+fn test_untidy_input() {
+    assert_eq!(generator(&input::untidy("18")), Ok(18));
+}
+
+#[test]
+fn test_params() {
+    let mut config = ::params::Config::new();
+    config.set(11, "grid_size", "0");
+    assert_eq!(
+        config.params::<Params>(11).unwrap_err().to_string(),
+        "day 11: invalid value \"0\" for `grid_size`: the squares must fit into the grid"
+    );
+    // The default of the other value is reported, if only one was set
+    let mut config = ::params::Config::new();
+    config.set(11, "area_size", "20");
+    assert_eq!(
+        config.params::<Params>(11).unwrap_err().to_string(),
+        "day 11: invalid value \"19\" for `max_area_size`: must not be smaller than `area_size`"
+    );
+    config.set(11, "area_size", "0");
+    assert!(config.params::<Params>(11).is_err());
+    config.set(11, "grid_size", "3");
+    config.set(11, "area_size", "3");
+    config.set(11, "max_area_size", "3");
+    assert!(config.params::<Params>(11).is_ok());
 }
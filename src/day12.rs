@@ -1,72 +1,268 @@
+//! Day 12: Subterranean Sustainability
+
+use budget::{Budget, BudgetExceeded};
+use error::{self, Line, ParseError};
+use input;
+use parse::Cursor;
+use simulation::{Simulation, StateKey};
+
 const PREFIX: &str = "initial state: ";
-// 20 generations, each allows to jump at most 2 places to the left
-const OFFSET: usize = 2 * 20;
 
+params! {
+    /// Parameters of the plant simulation
+    pub struct Params {
+        /// Number of generations to simulate in part 1
+        generations: usize = 20,
+        /// Number of generations to simulate in part 2
+        long_generations: usize = 50_000_000_000,
+        /// Maximal number of generations in part 2, before the pattern repeats, to simulate, 0 for no limit
+        max_steps: u64 = 1_000_000,
+        /// Maximal time to search in milliseconds, 0 for no limit
+        timeout_ms: u64 = 0,
+    }
+}
+
+/// The pot in the middle of `pattern` has a plant in the next generation if `new_state` is true
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Rule {
-    pattern: Vec<bool>,
-    new_state: bool,
+    /// Five pots, `true` if the pot contains a plant
+    pub pattern: Vec<bool>,
+    pub new_state: bool,
 }
 
-fn str_to_iter_bool<'a>(s: &'a str) -> impl Iterator<Item = bool> + 'a {
-    s.chars().map(|c| c == '#')
+/// Parse a single pot, where `#` contains a plant and `.` is empty
+fn parse_pot(cursor: &mut Cursor) -> Result<bool, ParseError> {
+    let c = cursor.char_if("'#' or '.'", |c| c == '#' || c == '.')?;
+    Ok(c == '#')
 }
 
 #[aoc_generator(day12)]
-pub fn generator(input: &str) -> (Vec<bool>, Vec<Rule>) {
-    let mut lines = input.lines();
-    // Create OFFSET `false` entries, then followed be the plants
-    let plants: Vec<bool> = (0..OFFSET)
-        .map(|_| false)
-        .chain(str_to_iter_bool(&lines.next().unwrap()[PREFIX.len()..]))
-        .chain((0..OFFSET).map(|_| false))
-        .collect();
+pub fn generator(input: &str) -> Result<(Vec<bool>, Vec<Rule>), ParseError> {
+    let input = &input::normalize(input);
+    let mut lines = error::lines(12, input);
+    let first_line = lines.next().unwrap_or_else(|| Line::new(12, 1, ""));
+    let mut cursor = Cursor::new(first_line);
+    cursor.literal(PREFIX)?;
+    let mut plants = Vec::new();
+    while !cursor.is_end() {
+        plants.push(parse_pot(&mut cursor)?);
+    }
 
     // skip empty lines
-    lines.next();
+    if let Some(line) = lines.next() {
+        if !line.text().is_empty() {
+            return Err(line.error_at(line.text(), "an empty line"));
+        }
+    }
 
     let rules: Vec<_> = lines
-        .map(|l| Rule {
-            pattern: str_to_iter_bool(&l[0..5]).collect(),
-            new_state: &l[9..10] == "#",
+        .map(|line| {
+            // parse a line like: "...## => #"
+            let mut cursor = Cursor::new(line);
+            let pattern = (0..5)
+                .map(|_| parse_pot(&mut cursor))
+                .collect::<Result<_, _>>()?;
+            cursor.literal(" => ")?;
+            let new_state = parse_pot(&mut cursor)?;
+            cursor.end()?;
+            Ok(Rule { pattern, new_state })
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    (plants, rules)
+    Ok((plants, rules))
 }
 
 #[aoc(day12, part1)]
-pub fn solve_part1((plants, rules): &(Vec<bool>, Vec<Rule>)) -> isize {
-    let mut plants = plants.clone();
+pub fn solve_part1(input: &(Vec<bool>, Vec<Rule>)) -> isize {
+    solve_part1_with(input, &Params::default())
+}
+
+pub fn solve_part1_with((plants, rules): &(Vec<bool>, Vec<Rule>), params: &Params) -> isize {
+    let mut pots = Pots::new(plants, rules);
+    pots.run(params.generations);
+    pots.sum() as isize
+}
+
+#[aoc(day12, part2)]
+pub fn solve_part2(input: &(Vec<bool>, Vec<Rule>)) -> Result<i64, BudgetExceeded> {
+    solve_part2_with(input, &Params::default())
+}
+
+pub fn solve_part2_with(
+    (plants, rules): &(Vec<bool>, Vec<Rule>),
+    params: &Params,
+) -> Result<i64, BudgetExceeded> {
+    // Eventually the plants form a pattern, which only moves along the row
+    let mut pots = Pots::new(plants, rules);
+    let budget = Budget::from_limits(params.max_steps, params.timeout_ms);
+    let shift = pots.fast_forward_within(params.long_generations, &budget)?;
+    let count = pots.plants.iter().filter(|&&has_plant| has_plant).count() as i64;
+    Ok(pots.sum() + shift * count)
+}
+
+/// The row of pots, trimmed to the range between the first and the last plant
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Pots {
+    /// `true` if the pot contains a plant
+    pub plants: Vec<bool>,
+    /// Number of the first pot in `plants`
+    pub first: i64,
+    pub rules: Vec<Rule>,
+}
+
+impl Pots {
+    /// The initial state, where `plants` starts at pot 0
+    pub fn new(plants: &[bool], rules: &[Rule]) -> Self {
+        let mut pots = Pots {
+            plants: plants.to_vec(),
+            first: 0,
+            rules: rules.to_vec(),
+        };
+        pots.trim();
+        pots
+    }
 
-    for _ in 0..20 {
-        // Perform one step
-        let new_plants: Vec<bool> = [false, false]
+    fn trim(&mut self) {
+        let leading = self.plants.iter().take_while(|&&p| !p).count();
+        let trailing = self.plants[leading..]
             .iter()
+            .rev()
+            .take_while(|&&p| !p)
+            .count();
+        let len = self.plants.len();
+        self.plants.truncate(len - trailing);
+        self.plants.drain(..leading);
+        self.first += leading as i64;
+    }
+
+    /// Sum of the numbers of all pots which contain a plant
+    pub fn sum(&self) -> i64 {
+        self.plants
+            .iter()
+            .enumerate()
+            .filter(|(_, &has_plant)| has_plant)
+            .map(|(i, _)| self.first + i as i64)
+            .sum()
+    }
+}
+
+impl Simulation for Pots {
+    fn step(&mut self) {
+        // Plants spread at most 2 pots to each side and `next_generation` keeps 2 pots at each end empty
+        let padding = [false; 4];
+        let padded: Vec<bool> = padding
+            .iter()
+            .chain(&self.plants)
+            .chain(&padding)
             .cloned()
-            .chain(plants.windows(5).map(|window| {
-                for rule in rules {
-                    if rule.pattern == window {
-                        return rule.new_state;
-                    }
-                }
-                unreachable!("There must always be a rule matching the window.")
-            }))
-            .chain([false, false].iter().cloned())
             .collect();
+        self.plants = next_generation(&padded, &self.rules);
+        self.first -= padding.len() as i64;
+        self.trim();
+    }
+}
+
+impl StateKey for Pots {
+    type Key = Vec<bool>;
 
-        assert_eq!(plants.len(), new_plants.len());
-        plants = new_plants;
+    fn key(&self) -> Vec<bool> {
+        self.plants.clone()
     }
 
-    plants
+    fn offset(&self) -> i64 {
+        self.first
+    }
+}
+
+/// Apply the rules to all pots at once
+///
+/// Patterns without a rule produce an empty pot, like in the example which only lists the rules
+/// creating a plant.
+/// The two pots at each end stay empty, so the row must be padded with enough empty pots.
+pub fn next_generation(plants: &[bool], rules: &[Rule]) -> Vec<bool> {
+    let new_plants: Vec<bool> = [false, false]
+        .iter()
+        .cloned()
+        .chain(plants.windows(5).map(|window| {
+            rules
+                .iter()
+                .find(|rule| rule.pattern == window)
+                .is_some_and(|rule| rule.new_state)
+        }))
+        .chain([false, false].iter().cloned())
+        .collect();
+
+    assert_eq!(plants.len(), new_plants.len());
+    new_plants
+}
+
+#[cfg(test)]
+const TEST_INPUT: &str = r#"initial state: #..#.#..##......###...###
+
+...## => #
+..#.. => #
+.#... => #
+.#.#. => #
+.#.## => #
+.##.. => #
+.#### => #
+#.#.# => #
+#.### => #
+##.#. => #
+##.## => #
+###.. => #
+###.# => #
+####. => #
+"#;
+
+#[test]
+fn test_part_1() {
+    assert_eq!(solve_part1(&generator(TEST_INPUT).unwrap()), 325);
+}
+
+#[test]
+fn test_missing_rules() {
+    // The verbatim example only lists the rules which create a plant
+    let (plants, rules) = generator(TEST_INPUT).unwrap();
+    let plants: Vec<bool> = [false; 4]
+        .iter()
+        .chain(&plants)
+        .chain(&[false; 4])
+        .cloned()
+        .collect();
+    let next: String = next_generation(&plants, &rules)
         .into_iter()
-        .enumerate()
-        .map(|(i, has_plant)| {
-            if has_plant {
-                i as isize - OFFSET as isize
-            } else {
-                0
-            }
-        })
-        .sum()
+        .map(|plant| if plant { '#' } else { '.' })
+        .collect();
+    assert_eq!(next, "....#...#....#.....#..#..#..#....");
+}
+
+#[test]
+fn test_untidy_input() {
+    assert_eq!(generator(&input::untidy(TEST_INPUT)), generator(TEST_INPUT));
+}
+
+#[test]
+fn test_part_2_with() {
+    // Compare skipping the cycles with simulating every generation
+    let input = generator(TEST_INPUT).unwrap();
+    for &generations in &[20, 100, 321] {
+        let params = Params {
+            generations,
+            long_generations: generations,
+            ..Params::default()
+        };
+        assert_eq!(
+            solve_part2_with(&input, &params),
+            Ok(solve_part1_with(&input, &params) as i64)
+        );
+    }
+}
+
+#[test]
+fn test_generator_error() {
+    let err = generator("initial state: #..#\n\n...## => x").unwrap_err();
+    assert_eq!(err, ParseError::new(12, 3, 10, "'#' or '.'", "\"x\""));
 }
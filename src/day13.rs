@@ -1,19 +1,48 @@
+//! Day 13: Mine Cart Madness
+
+use budget::{Budget, BudgetExceeded};
+use error::ParseError;
+use geometry::{Direction, Point};
+use grid::Grid;
+use input;
+use observer::{Observer, Silent, Summary};
 #[cfg(test)]
 use pretty_assertions::assert_eq;
+use simulation::Simulation;
 use std::{
+    error::Error,
     fmt::{self, Display},
     ops::Index,
 };
 
+params! {
+    /// Limits for waiting on crashes
+    pub struct Params {
+        /// Maximal number of ticks to simulate, 0 for no limit
+        max_steps: u64 = 1_000_000,
+        /// Maximal time to search in milliseconds, 0 for no limit
+        timeout_ms: u64 = 0,
+    }
+}
+
+/// The tracks and the carts currently on them
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Board {
-    fields: Vec<Vec<Field>>,
-    carts: Vec<Cart>,
+    pub fields: Grid<Field>,
+    pub carts: Vec<Cart>,
+    /// Positions of all crashes so far, in the order they happened
+    pub crashes: Vec<Coordinate>,
+    /// Position at which a cart left the track, no cart moves after that
+    pub off_track: Option<Coordinate>,
 }
 
-impl Board {
-    /// Returns the Coordinate of the collision, if any happen
-    fn step(&mut self, remove_crashes: bool) -> Option<Coordinate> {
+impl Simulation for Board {
+    /// Move every cart once, in reading order
+    ///
+    /// Carts which crash are removed from the board and the position of the crash is recorded.
+    /// A cart leaving the track stops the simulation.
+    fn step(&mut self) {
         let mut carts_sorted = self.carts.clone();
         carts_sorted.sort_by_key(|c| c.position);
         let mut new_carts = Vec::new();
@@ -27,7 +56,10 @@ impl Board {
             }
 
             let mut cart = *cart;
-            cart.r#move(&self);
+            if cart.r#move(self).is_err() {
+                self.off_track = Some(cart.position);
+                return;
+            }
             // Check Collision
             if let Some((j, _)) = new_carts
                 .iter()
@@ -35,9 +67,7 @@ impl Board {
                 .find(|(_, c): &(usize, &Cart)| c.position == cart.position)
             {
                 // Check already moved carts
-                if !remove_crashes {
-                    return Some(cart.position);
-                }
+                self.crashes.push(cart.position);
                 new_carts.remove(j);
             } else if let Some((j, _)) = carts_sorted[i + 1..]
                 .iter()
@@ -45,32 +75,41 @@ impl Board {
                 .find(|(_, c): &(usize, &Cart)| c.position == cart.position)
             {
                 // Check not moved carts
-                if !remove_crashes {
-                    return Some(cart.position);
-                }
+                self.crashes.push(cart.position);
                 skiplist.push(i + j + 1);
             } else {
                 new_carts.push(cart);
             }
         }
         self.carts = new_carts;
-        // No collision as otherwise we already exited
-        None
+    }
+
+    /// With less than two carts, no more crashes can happen
+    fn is_finished(&self) -> bool {
+        self.carts.len() < 2 || self.off_track.is_some()
+    }
+}
+
+impl Summary for Board {
+    fn summary(&self) -> String {
+        format!("{} carts, {} crashes", self.carts.len(), self.crashes.len())
     }
 }
 
 impl Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        for (y, row) in self.fields.iter().enumerate() {
+        for (y, row) in self.fields.rows().enumerate() {
+            let mut line = String::with_capacity(row.len());
             for (x, col) in row.iter().enumerate() {
-                let coord = Coordinate { x, y };
+                let coord = Coordinate::new(x as isize, y as isize);
                 if let Some(cart) = self.carts.iter().find(|c| c.position == coord) {
-                    write!(f, "{}", cart)?;
+                    line.push_str(&cart.to_string());
                 } else {
-                    write!(f, "{}", col)?;
+                    line.push_str(&col.to_string());
                 }
             }
-            writeln!(f)?;
+            // The grid pads short lines with empty fields, which should not be printed
+            writeln!(f, "{}", line.trim_end())?;
         }
         Ok(())
     }
@@ -80,11 +119,13 @@ impl Index<Coordinate> for Board {
     type Output = Field;
 
     fn index(&self, coord: Coordinate) -> &Self::Output {
-        &self.fields[coord.y][coord.x]
+        &self.fields[coord]
     }
 }
 
+/// A single piece of track
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Field {
     // No Track
     Empty,
@@ -101,8 +142,11 @@ pub enum Field {
 }
 
 impl Field {
-    fn from_char(c: char, last_field: Field) -> Self {
-        match (c, last_field) {
+    /// Parse a track character, `last_field` is the field to the left
+    ///
+    /// Returns `None` for unknown track characters
+    pub fn from_char(c: char, last_field: Field) -> Option<Self> {
+        Some(match (c, last_field) {
             (' ', _) => Field::Empty,
             ('|', _) | ('^', _) | ('v', _) => Field::NorthSouth,
             ('-', _) | ('<', _) | ('>', _) => Field::EastWest,
@@ -124,8 +168,8 @@ impl Field {
             | ('\\', Field::EastWest)
             | ('\\', Field::NorthEast) => Field::SouthWest,
             ('\\', _) => Field::NorthEast,
-            (c, _) => unreachable!(format!("Unknown track character: '{}'", c)),
-        }
+            (_, _) => return None,
+        })
     }
 }
 
@@ -146,39 +190,19 @@ impl Display for Field {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-pub struct Coordinate {
-    y: usize,
-    x: usize,
-}
-
-impl Coordinate {
-    fn r#move(&mut self, orientation: Orientation) {
-        // Move position, then rotate if on a curve
-        match orientation {
-            Orientation::North => self.y -= 1,
-            Orientation::East => self.x += 1,
-            Orientation::South => self.y += 1,
-            Orientation::West => self.x -= 1,
-        };
-    }
-}
-
-impl Display for Coordinate {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(f, "{},{}", self.x, self.y)
-    }
-}
+/// Position on the board, ordered top to bottom, then left to right
+pub type Coordinate = Point<isize>;
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Cart {
-    position: Coordinate,
-    orientation: Orientation,
-    next_crossing_turn: NextCrossingTurn,
+    pub position: Coordinate,
+    pub orientation: Direction,
+    pub next_crossing_turn: NextCrossingTurn,
 }
 
 impl Cart {
-    fn new(position: Coordinate, orientation: Orientation) -> Self {
+    pub fn new(position: Coordinate, orientation: Direction) -> Self {
         Cart {
             position,
             orientation,
@@ -186,31 +210,38 @@ impl Cart {
         }
     }
 
-    fn r#move(&mut self, board: &Board) {
+    /// Move one field forward and turn according to the track
+    ///
+    /// Fails if the new position is not on the track.
+    pub fn r#move(&mut self, board: &Board) -> Result<(), CrashError> {
         // Move position, then rotate if on a curve
-        self.position.r#move(self.orientation);
+        self.position = self.position.step(self.orientation);
+        let field = match board.fields.get(self.position) {
+            Some(&Field::Empty) | None => return Err(CrashError::OffTrack(self.position)),
+            Some(&field) => field,
+        };
 
         // Rotate if on curve
-        match (board[self.position], self.orientation) {
+        match (field, self.orientation) {
             // The current orientation is always opposite to the field orientation,
             // but the output orientation must be one of the field orientations
-            (Field::NorthEast, Orientation::South) => self.orientation = Orientation::East,
-            (Field::NorthEast, Orientation::West) => self.orientation = Orientation::North,
+            (Field::NorthEast, Direction::South) => self.orientation = Direction::East,
+            (Field::NorthEast, Direction::West) => self.orientation = Direction::North,
 
-            (Field::NorthWest, Orientation::East) => self.orientation = Orientation::North,
-            (Field::NorthWest, Orientation::South) => self.orientation = Orientation::West,
+            (Field::NorthWest, Direction::East) => self.orientation = Direction::North,
+            (Field::NorthWest, Direction::South) => self.orientation = Direction::West,
 
-            (Field::EastSouth, Orientation::North) => self.orientation = Orientation::East,
-            (Field::EastSouth, Orientation::West) => self.orientation = Orientation::South,
+            (Field::EastSouth, Direction::North) => self.orientation = Direction::East,
+            (Field::EastSouth, Direction::West) => self.orientation = Direction::South,
 
-            (Field::SouthWest, Orientation::North) => self.orientation = Orientation::West,
-            (Field::SouthWest, Orientation::East) => self.orientation = Orientation::South,
+            (Field::SouthWest, Direction::North) => self.orientation = Direction::West,
+            (Field::SouthWest, Direction::East) => self.orientation = Direction::South,
 
             // Special crossing rules
             (Field::NorthEastSouthWest, _) => {
                 match self.next_crossing_turn {
-                    NextCrossingTurn::Left => self.orientation.left(),
-                    NextCrossingTurn::Right => self.orientation.right(),
+                    NextCrossingTurn::Left => self.orientation = self.orientation.turn_left(),
+                    NextCrossingTurn::Right => self.orientation = self.orientation.turn_right(),
                     // Nothing to do
                     NextCrossingTurn::Straight => {}
                 }
@@ -222,6 +253,7 @@ impl Cart {
             // Nothing to do if not on a curve or crossing
             (_, _) => {}
         }
+        Ok(())
     }
 }
 
@@ -231,43 +263,18 @@ impl Display for Cart {
             f,
             "{}",
             match self.orientation {
-                Orientation::North => '^',
-                Orientation::East => '>',
-                Orientation::South => 'v',
-                Orientation::West => '<',
+                Direction::North => '^',
+                Direction::East => '>',
+                Direction::South => 'v',
+                Direction::West => '<',
             }
         )
     }
 }
 
+/// Direction a cart takes at the next crossing, they cycle through left, straight and right
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-pub enum Orientation {
-    North,
-    East,
-    South,
-    West,
-}
-
-impl Orientation {
-    fn left(&mut self) {
-        *self = match *self {
-            Orientation::North => Orientation::West,
-            Orientation::East => Orientation::North,
-            Orientation::South => Orientation::East,
-            Orientation::West => Orientation::South,
-        }
-    }
-    fn right(&mut self) {
-        *self = match *self {
-            Orientation::North => Orientation::East,
-            Orientation::East => Orientation::South,
-            Orientation::South => Orientation::West,
-            Orientation::West => Orientation::North,
-        }
-    }
-}
-
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum NextCrossingTurn {
     Left,
     Straight,
@@ -275,11 +282,11 @@ pub enum NextCrossingTurn {
 }
 
 impl NextCrossingTurn {
-    fn new() -> Self {
+    pub fn new() -> Self {
         NextCrossingTurn::Left
     }
 
-    fn next(&mut self) {
+    pub fn next(&mut self) {
         *self = match *self {
             NextCrossingTurn::Left => NextCrossingTurn::Straight,
             NextCrossingTurn::Straight => NextCrossingTurn::Right,
@@ -288,58 +295,128 @@ impl NextCrossingTurn {
     }
 }
 
+impl Default for NextCrossingTurn {
+    fn default() -> Self {
+        NextCrossingTurn::new()
+    }
+}
+
 #[aoc_generator(day13)]
-pub fn generator(input: &str) -> Board {
+pub fn generator(input: &str) -> Result<Board, ParseError> {
+    let input = &input::normalize(input);
     let mut carts = Vec::new();
-    let board: Vec<Vec<Field>> = input
-        .lines()
-        .enumerate()
-        .map(|(y, line)| {
-            let mut last_field = Field::Empty;
+    let mut last_field = Field::Empty;
+    let fields = Grid::parse(13, input, Field::Empty, "a track or cart", |position, c| {
+        match c {
+            '^' => carts.push(Cart::new(position, Direction::North)),
+            '>' => carts.push(Cart::new(position, Direction::East)),
+            'v' => carts.push(Cart::new(position, Direction::South)),
+            '<' => carts.push(Cart::new(position, Direction::West)),
+            // Not a cart
+            _ => {}
+        }
 
-            line.chars()
-                .enumerate()
-                .map(|(x, c)| {
-                    match c {
-                        '^' => carts.push(Cart::new(Coordinate { x, y }, Orientation::North)),
-                        '>' => carts.push(Cart::new(Coordinate { x, y }, Orientation::East)),
-                        'v' => carts.push(Cart::new(Coordinate { x, y }, Orientation::South)),
-                        '<' => carts.push(Cart::new(Coordinate { x, y }, Orientation::West)),
-                        // Not a cart
-                        _ => {}
-                    }
-
-                    last_field = Field::from_char(c, last_field);
-                    last_field
-                })
-                .collect()
-        })
-        .collect();
-    Board {
-        fields: board,
+        // Each line starts without a previous field
+        if position.x == 0 {
+            last_field = Field::Empty;
+        }
+        last_field = Field::from_char(c, last_field)?;
+        Some(last_field)
+    })?;
+    Ok(Board {
+        fields,
         carts,
+        crashes: Vec::new(),
+        off_track: None,
+    })
+}
+
+/// Why the carts never reach the state a part searches for
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CrashError {
+    Budget(BudgetExceeded),
+    /// Less than two carts are left, before any crashed
+    NoCrash,
+    /// All carts crashed, none is left
+    NoCartLeft,
+    /// A cart left the track at this position
+    OffTrack(Coordinate),
+}
+
+impl From<BudgetExceeded> for CrashError {
+    fn from(err: BudgetExceeded) -> Self {
+        CrashError::Budget(err)
     }
 }
 
+impl Display for CrashError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            CrashError::Budget(err) => err.fmt(f),
+            CrashError::NoCrash => write!(f, "less than two carts, which could crash"),
+            CrashError::NoCartLeft => write!(f, "all carts crashed, none is left"),
+            CrashError::OffTrack(position) => {
+                write!(f, "a cart left the track at {},{}", position.x, position.y)
+            }
+        }
+    }
+}
+
+impl Error for CrashError {}
+
 #[aoc(day13, part1)]
-pub fn solve_part1(board: &Board) -> Coordinate {
+pub fn solve_part1(board: &Board) -> Result<Coordinate, CrashError> {
+    solve_part1_with(board, &Params::default())
+}
+
+/// Position of the first crash
+pub fn solve_part1_with(board: &Board, params: &Params) -> Result<Coordinate, CrashError> {
+    solve_part1_observed(board, params, &mut Silent)
+}
+
+/// Like `solve_part1_with`, but report the board to `observer` while waiting for the crash
+pub fn solve_part1_observed<O: Observer<Board>>(
+    board: &Board,
+    params: &Params,
+    observer: &mut O,
+) -> Result<Coordinate, CrashError> {
     let mut board: Board = board.clone();
-    loop {
-        if let Some(coord) = board.step(false) {
-            return coord;
-        }
+    let budget = Budget::from_limits(params.max_steps, params.timeout_ms);
+    let crashed = board.run_until_observed(&budget, |board| !board.crashes.is_empty(), observer)?;
+    if let Some(position) = board.off_track {
+        return Err(CrashError::OffTrack(position));
     }
+    crashed.ok_or(CrashError::NoCrash)?;
+    Ok(board.crashes[0])
 }
 
 #[aoc(day13, part2)]
-pub fn solve_part2(board: &Board) -> Coordinate {
+pub fn solve_part2(board: &Board) -> Result<Coordinate, CrashError> {
+    solve_part2_with(board, &Params::default())
+}
+
+/// Position of the last cart, after all others crashed
+pub fn solve_part2_with(board: &Board, params: &Params) -> Result<Coordinate, CrashError> {
+    solve_part2_observed(board, params, &mut Silent)
+}
+
+/// Like `solve_part2_with`, but report the board to `observer` while the carts crash
+pub fn solve_part2_observed<O: Observer<Board>>(
+    board: &Board,
+    params: &Params,
+    observer: &mut O,
+) -> Result<Coordinate, CrashError> {
     let mut board: Board = board.clone();
-    loop {
-        board.step(true);
-        if board.carts.len() == 1 {
-            return board.carts[0].position
-        }
+    let budget = Budget::from_limits(params.max_steps, params.timeout_ms);
+    board.run_until_observed(&budget, Board::is_finished, observer)?;
+    if let Some(position) = board.off_track {
+        return Err(CrashError::OffTrack(position));
     }
+    board
+        .carts
+        .first()
+        .map(|cart| cart.position)
+        .ok_or(CrashError::NoCartLeft)
 }
 
 #[cfg(test)]
@@ -379,42 +456,62 @@ const TEST_INPUT_STEP_TEN: &str = r"/---\
 
 #[test]
 fn test_part1_step_one() {
-    let mut board = generator(TEST_INPUT);
-    board.step(false);
+    let mut board = generator(TEST_INPUT).unwrap();
+    board.step();
     assert_eq!(board.to_string(), TEST_INPUT_STEP_ONE);
 }
 
 #[test]
 fn test_part1_step_two() {
-    let mut board = generator(TEST_INPUT);
-    println!("{:?}", board.fields[0]);
-    board.step(false);
-    board.step(false);
+    let mut board = generator(TEST_INPUT).unwrap();
+    println!("{:?}", board.fields.row(0).collect::<Vec<_>>());
+    board.step();
+    board.step();
     assert_eq!(board.to_string(), TEST_INPUT_STEP_TWO);
 }
 
 #[test]
 fn test_part1_step_ten() {
-    let mut board = generator(TEST_INPUT);
-    println!("{:?}", board.fields[0]);
+    let mut board = generator(TEST_INPUT).unwrap();
+    println!("{:?}", board.fields.row(0).collect::<Vec<_>>());
     for _ in 0..10 {
-        board.step(false);
+        board.step();
     }
     assert_eq!(board.to_string(), TEST_INPUT_STEP_TEN);
 }
 
 #[test]
 fn test_part1() {
-    let board = generator(TEST_INPUT);
+    let board = generator(TEST_INPUT).unwrap();
     let res = solve_part1(&board);
-    assert_eq!(res, Coordinate { x: 7, y: 3 });
+    assert_eq!(res, Ok(Coordinate { x: 7, y: 3 }));
 }
 
 #[test]
 fn test_part1_self() {
-    let board = generator(">--<");
+    let board = generator(">--<").unwrap();
     let res = solve_part1(&board);
-    assert_eq!(res, Coordinate { x: 2, y: 0 });
+    assert_eq!(res, Ok(Coordinate { x: 2, y: 0 }));
+}
+
+#[test]
+fn test_part1_observed() {
+    let board = generator(TEST_INPUT).unwrap();
+    let mut boards = Vec::new();
+    let res = solve_part1_observed(&board, &Params::default(), &mut |_, board: &Board| {
+        boards.push(board.to_string())
+    });
+    assert_eq!(res, Ok(Coordinate { x: 7, y: 3 }));
+    assert_eq!(boards.len(), 15);
+    assert_eq!(boards[1], TEST_INPUT_STEP_ONE);
+    assert_eq!(boards[2], TEST_INPUT_STEP_TWO);
+    assert_eq!(boards[10], TEST_INPUT_STEP_TEN);
+}
+
+#[test]
+fn test_untidy_input() {
+    let board = generator(&input::untidy(TEST_INPUT)).unwrap();
+    assert_eq!(solve_part1(&board), Ok(Coordinate { x: 7, y: 3 }));
 }
 
 #[cfg(test)]
@@ -459,40 +556,108 @@ const TEST_INPUT_PART_2_STEP_THREE: &str = r"/---\
 
 #[test]
 fn test_part2_step_one() {
-    let mut board = generator(TEST_INPUT_PART_2_INPUT);
+    let mut board = generator(TEST_INPUT_PART_2_INPUT).unwrap();
     println!("{}", board);
-    board.step(true);
+    board.step();
     println!("{}", board);
     assert_eq!(board.to_string(), TEST_INPUT_PART_2_STEP_ONE);
 }
 
 #[test]
 fn test_part2_step_two() {
-    let mut board = generator(TEST_INPUT_PART_2_INPUT);
+    let mut board = generator(TEST_INPUT_PART_2_INPUT).unwrap();
     println!("{}", board);
-    board.step(true);
+    board.step();
     println!("{}", board);
-    board.step(true);
+    board.step();
     println!("{}", board);
     assert_eq!(board.to_string(), TEST_INPUT_PART_2_STEP_TWO);
 }
 
 #[test]
 fn test_part2_step_three() {
-    let mut board = generator(TEST_INPUT_PART_2_INPUT);
+    let mut board = generator(TEST_INPUT_PART_2_INPUT).unwrap();
     println!("{}", board);
-    board.step(true);
+    board.step();
     println!("{}", board);
-    board.step(true);
+    board.step();
     println!("{}", board);
-    board.step(true);
+    board.step();
     println!("{}", board);
     assert_eq!(board.to_string(), TEST_INPUT_PART_2_STEP_THREE);
 }
 
 #[test]
 fn test_part2() {
-    let board = generator(TEST_INPUT_PART_2_INPUT);
+    let board = generator(TEST_INPUT_PART_2_INPUT).unwrap();
     let res = solve_part2(&board);
-    assert_eq!(res, Coordinate { x: 6, y: 4 });
+    assert_eq!(res, Ok(Coordinate { x: 6, y: 4 }));
+}
+
+#[test]
+fn test_part2_observed() {
+    let board = generator(TEST_INPUT_PART_2_INPUT).unwrap();
+    let mut summaries = Vec::new();
+    let res = solve_part2_observed(&board, &Params::default(), &mut |steps, board: &Board| {
+        summaries.push((steps, board.summary()))
+    });
+    assert_eq!(res, Ok(Coordinate { x: 6, y: 4 }));
+    assert_eq!(
+        summaries,
+        vec![
+            (0, "9 carts, 0 crashes".to_string()),
+            (1, "3 carts, 3 crashes".to_string()),
+            (2, "3 carts, 3 crashes".to_string()),
+            (3, "1 carts, 4 crashes".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_part2_budget() {
+    // Two carts going around separate loops never crash
+    let board = generator("/>\\ /<\\\n\\-/ \\-/").unwrap();
+    let params = Params {
+        max_steps: 100,
+        timeout_ms: 0,
+    };
+    assert_eq!(
+        solve_part2_with(&board, &params),
+        Err(CrashError::Budget(BudgetExceeded::Steps(100)))
+    );
+}
+
+#[test]
+fn test_no_crash() {
+    let board = generator("/->-\\\n|   |\n\\---/").unwrap();
+    assert_eq!(solve_part1(&board), Err(CrashError::NoCrash));
+    assert_eq!(solve_part2(&board), Ok(Coordinate { x: 2, y: 0 }));
+    let board = generator("/---\\\n|   |\n\\---/").unwrap();
+    assert_eq!(solve_part1(&board), Err(CrashError::NoCrash));
+    assert_eq!(solve_part2(&board), Err(CrashError::NoCartLeft));
+}
+
+#[test]
+fn test_part2_all_crashed() {
+    let board = generator("->--<-").unwrap();
+    assert_eq!(solve_part1(&board), Ok(Coordinate { x: 3, y: 0 }));
+    assert_eq!(solve_part2(&board), Err(CrashError::NoCartLeft));
+}
+
+#[test]
+fn test_off_track() {
+    let board = generator("<-->").unwrap();
+    let position = Coordinate { x: -1, y: 0 };
+    assert_eq!(solve_part1(&board), Err(CrashError::OffTrack(position)));
+    assert_eq!(solve_part2(&board), Err(CrashError::OffTrack(position)));
+    // A gap within the grid is not part of the track either
+    let board = generator("-> -<").unwrap();
+    let position = Coordinate { x: 2, y: 0 };
+    assert_eq!(solve_part1(&board), Err(CrashError::OffTrack(position)));
+}
+
+#[test]
+fn test_generator_error() {
+    let err = generator("/->-\\\n|   #").unwrap_err();
+    assert_eq!(err, ParseError::new(13, 2, 5, "a track or cart", "\"#\""));
 }
@@ -1,15 +1,37 @@
+//! Day 14: Chocolate Charts
+
+use budget::{Budget, BudgetExceeded};
+use error::{self, NoAnswer, ParseError};
+use input;
+use observer::{Observer, Silent, Summary};
 #[cfg(test)]
 use pretty_assertions::assert_eq;
+use simulation::Simulation;
 use std::fmt::{self, Display};
 
-struct State {
-    receipies: Vec<u8>,
-    first_elf: usize,
-    second_elf: usize,
+params! {
+    /// Limits for the search in part 2
+    pub struct Params {
+        /// Maximal number of steps to simulate, 0 for no limit
+        max_steps: u64 = 100_000_000,
+        /// Maximal time to search in milliseconds, 0 for no limit
+        timeout_ms: u64 = 0,
+    }
+}
+
+/// The scoreboard and the current receipies of both elves
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct State {
+    pub receipies: Vec<u8>,
+    /// Index of the current receipe of the first elf
+    pub first_elf: usize,
+    pub second_elf: usize,
 }
 
 impl State {
-    fn new() -> Self {
+    /// The initial scoreboard `3 7`
+    pub fn new() -> Self {
         Self {
             receipies: vec![3, 7],
             first_elf: 0,
@@ -17,24 +39,8 @@ impl State {
         }
     }
 
-    fn step(&mut self) {
-        // Make new receipies
-        let r_elf_1 = self.receipies[self.first_elf];
-        let r_elf_2 = self.receipies[self.second_elf];
-        let new_r = r_elf_1 + r_elf_2;
-        let r1 = new_r / 10;
-        let r2 = new_r % 10;
-        if r1 > 0 {
-            self.receipies.push(r1);
-        }
-        self.receipies.push(r2);
-
-        // Switch receipies of elves
-        self.first_elf = (self.first_elf + 1 + r_elf_1 as usize) % self.receipies.len();
-        self.second_elf = (self.second_elf + 1 + r_elf_2 as usize) % self.receipies.len();
-    }
-
-    fn take_10_after_index(&self, ind: usize) -> String {
+    /// Scores of the ten receipies after the first `ind` ones
+    pub fn take_10_after_index(&self, ind: usize) -> String {
         self.receipies
             .iter()
             .skip(ind)
@@ -43,8 +49,11 @@ impl State {
             .collect()
     }
 
+    /// Number of receipies before `needle`, if the scoreboard ends with `needle`
+    ///
+    /// The needle may also end one before the last receipe, since a step can add two receipies.
     #[allow(clippy::int_plus_one)]
-    fn ends_with(&self, needle: &[u8]) -> Option<usize> {
+    pub fn ends_with(&self, needle: &[u8]) -> Option<usize> {
         // Check how many receipies are before the needle
 
         // Each round adds either one or two receipies, therefore we test two positions
@@ -59,6 +68,38 @@ impl State {
     }
 }
 
+impl Simulation for State {
+    /// Create new receipies and move both elves
+    fn step(&mut self) {
+        // Make new receipies
+        let r_elf_1 = self.receipies[self.first_elf];
+        let r_elf_2 = self.receipies[self.second_elf];
+        let new_r = r_elf_1 + r_elf_2;
+        let r1 = new_r / 10;
+        let r2 = new_r % 10;
+        if r1 > 0 {
+            self.receipies.push(r1);
+        }
+        self.receipies.push(r2);
+
+        // Switch receipies of elves
+        self.first_elf = (self.first_elf + 1 + r_elf_1 as usize) % self.receipies.len();
+        self.second_elf = (self.second_elf + 1 + r_elf_2 as usize) % self.receipies.len();
+    }
+}
+
+impl Summary for State {
+    fn summary(&self) -> String {
+        format!("{} receipies", self.receipies.len())
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::new()
+    }
+}
+
 impl Display for State {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         for (i, r) in self.receipies.iter().enumerate() {
@@ -74,66 +115,174 @@ impl Display for State {
     }
 }
 
-#[aoc(day14, part1)]
-pub fn solve_part1(input: &str) -> String {
-    let input: usize = input.parse().unwrap();
-    let mut state = State::new();
+#[aoc_generator(day14, part1)]
+pub fn generator_part1(input: &str) -> Result<usize, ParseError> {
+    let input = &input::normalize(input);
+    let line = error::Line::new(14, 1, input);
+    line.parse(input, "a number of receipies")
+}
 
-    while state.receipies.len() < input + 10 {
-        state.step()
+#[aoc_generator(day14, part2)]
+pub fn generator_part2(input: &str) -> Result<Vec<u8>, ParseError> {
+    let input = &input::normalize(input);
+    if input.trim().is_empty() {
+        return Err(error::empty_input(14, "a sequence of digits"));
     }
+    let line = error::Line::new(14, 1, input);
+    input
+        .char_indices()
+        .map(|(idx, c)| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or_else(|| line.error_at(&input[idx..idx + c.len_utf8()], "a digit"))
+        })
+        .collect()
+}
 
-    state.take_10_after_index(input)
+#[aoc(day14, part1)]
+pub fn solve_part1(input: &usize) -> Result<String, NoAnswer> {
+    let input = *input;
+    let end = input
+        .checked_add(10)
+        .ok_or_else(|| NoAnswer::new(14, "the scoreboard cannot hold that many receipies"))?;
+    let mut state = State::new();
+    state.run_until(|state| state.receipies.len() >= end);
+    Ok(state.take_10_after_index(input))
 }
 
 #[aoc(day14, part2)]
-pub fn solve_part2(input: &str) -> usize {
-    let receipies: Vec<u8> = input.chars().map(|c| c as u8 - b'0').collect();
+pub fn solve_part2(receipies: &[u8]) -> Result<usize, BudgetExceeded> {
+    solve_part2_with(receipies, &Params::default())
+}
+
+/// Number of receipies before the sequence `receipies` first appears
+pub fn solve_part2_with(receipies: &[u8], params: &Params) -> Result<usize, BudgetExceeded> {
+    solve_part2_observed(receipies, params, &mut Silent)
+}
+
+/// Like `solve_part2_with`, but report the scoreboard to `observer` during the search
+pub fn solve_part2_observed<O: Observer<State>>(
+    receipies: &[u8],
+    params: &Params,
+    observer: &mut O,
+) -> Result<usize, BudgetExceeded> {
     let mut state = State::new();
-    loop {
-        state.step();
-        if let Some(ind) = state.ends_with(&receipies) {
-            return ind;
-        }
-    }
+    let budget = Budget::from_limits(params.max_steps, params.timeout_ms);
+    state.run_until_observed(
+        &budget,
+        |state| state.ends_with(receipies).is_some(),
+        observer,
+    )?;
+    Ok(state.ends_with(receipies).unwrap())
 }
 
 #[test]
 fn test_part1_5() {
-    assert_eq!(solve_part1("5"), "0124515891");
+    assert_eq!(
+        solve_part1(&generator_part1("5").unwrap()),
+        Ok("0124515891".to_string())
+    );
 }
 
 #[test]
 fn test_part1_9() {
-    assert_eq!(solve_part1("9"), "5158916779");
+    assert_eq!(
+        solve_part1(&generator_part1("9").unwrap()),
+        Ok("5158916779".to_string())
+    );
 }
 
 #[test]
 fn test_part1_18() {
-    assert_eq!(solve_part1("18"), "9251071085");
+    assert_eq!(
+        solve_part1(&generator_part1("18").unwrap()),
+        Ok("9251071085".to_string())
+    );
 }
 
 #[test]
 fn test_part1_2018() {
-    assert_eq!(solve_part1("2018"), "5941429882");
+    assert_eq!(
+        solve_part1(&generator_part1("2018").unwrap()),
+        Ok("5941429882".to_string())
+    );
+}
+
+#[test]
+fn test_part2_start() {
+    assert_eq!(solve_part2(&generator_part2("37").unwrap()), Ok(0));
 }
 
 #[test]
 fn test_part2_5() {
-    assert_eq!(solve_part2("01245"), 5);
+    assert_eq!(solve_part2(&generator_part2("01245").unwrap()), Ok(5));
 }
 
 #[test]
 fn test_part2_9() {
-    assert_eq!(solve_part2("51589"), 9);
+    assert_eq!(solve_part2(&generator_part2("51589").unwrap()), Ok(9));
 }
 
 #[test]
 fn test_part2_18() {
-    assert_eq!(solve_part2("92510"), 18);
+    assert_eq!(solve_part2(&generator_part2("92510").unwrap()), Ok(18));
 }
 
 #[test]
 fn test_part2_2018() {
-    assert_eq!(solve_part2("59414"), 2018);
+    assert_eq!(solve_part2(&generator_part2("59414").unwrap()), Ok(2018));
+}
+
+#[test]
+fn test_part2_observed() {
+    let mut scoreboards = Vec::new();
+    let res = solve_part2_observed(
+        &[0, 1, 2, 4, 5],
+        &Params::default(),
+        &mut |_, state: &State| scoreboards.push(state.take_10_after_index(0)),
+    );
+    assert_eq!(res, Ok(5));
+    assert_eq!(
+        scoreboards,
+        vec![
+            "37",
+            "3710",
+            "371010",
+            "3710101",
+            "37101012",
+            "371010124",
+            "3710101245"
+        ]
+    );
+}
+
+#[test]
+fn test_untidy_input() {
+    assert_eq!(generator_part1(&input::untidy("2018")), Ok(2018));
+    assert_eq!(
+        generator_part2(&input::untidy("59414")),
+        Ok(vec![5, 9, 4, 1, 4])
+    );
+}
+
+#[test]
+fn test_generator_error() {
+    let err = generator_part2("594x4").unwrap_err();
+    assert_eq!(err, ParseError::new(14, 1, 4, "a digit", "\"x\""));
+    let err = generator_part2(" \n").unwrap_err();
+    assert_eq!(
+        err,
+        ParseError::new(14, 1, 1, "a sequence of digits", "end of input")
+    );
+}
+
+#[test]
+fn test_part1_overflow() {
+    assert_eq!(
+        solve_part1(&usize::MAX),
+        Err(NoAnswer::new(
+            14,
+            "the scoreboard cannot hold that many receipies"
+        ))
+    );
 }
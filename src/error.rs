@@ -0,0 +1,205 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Describes why and where the puzzle input could not be parsed
+///
+/// Line and column numbers start at 1 and the column counts characters, not bytes.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    pub fn new(
+        day: u8,
+        line: usize,
+        column: usize,
+        expected: impl Into<String>,
+        found: impl Into<String>,
+    ) -> Self {
+        ParseError {
+            day,
+            line,
+            column,
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}, found {}",
+            self.day, self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Error for an input without any lines, `expected` describes the first line
+pub fn empty_input(day: u8, expected: impl Into<String>) -> ParseError {
+    ParseError::new(day, 1, 1, expected, "end of input")
+}
+
+/// The input parsed fine, but the puzzle has no answer for it
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct NoAnswer {
+    pub day: u8,
+    pub reason: &'static str,
+}
+
+impl NoAnswer {
+    pub fn new(day: u8, reason: &'static str) -> Self {
+        NoAnswer { day, reason }
+    }
+}
+
+impl Display for NoAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "day {}: no answer, {}", self.day, self.reason)
+    }
+}
+
+impl Error for NoAnswer {}
+
+/// A single line of the puzzle input, which knows its position for error reporting
+///
+/// All the `token` arguments must be slices of `text()`, such that the column can be calculated.
+#[derive(Copy, Clone, Debug)]
+pub struct Line<'a> {
+    day: u8,
+    number: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(day: u8, number: usize, text: &'a str) -> Self {
+        Line { day, number, text }
+    }
+
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Returns the 1-based column at which `token` starts
+    pub fn column_of(&self, token: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let pos = token.as_ptr() as usize;
+        if pos < start || pos > start + self.text.len() {
+            // Not a slice of this line
+            return 1;
+        }
+        self.text[..pos - start].chars().count() + 1
+    }
+
+    /// Error pointing to `token`, which was not what we `expected`
+    ///
+    /// For an empty `token` the following character is reported instead.
+    pub fn error_at(&self, token: &str, expected: impl Into<String>) -> ParseError {
+        let found = if token.is_empty() {
+            let offset = (token.as_ptr() as usize).saturating_sub(self.text.as_ptr() as usize);
+            match self.text.get(offset..).and_then(|rest| rest.chars().next()) {
+                Some(c) => format!("{:?}", c.to_string()),
+                None => "end of line".to_string(),
+            }
+        } else {
+            format!("{:?}", token)
+        };
+        ParseError::new(
+            self.day,
+            self.number,
+            self.column_of(token),
+            expected,
+            found,
+        )
+    }
+
+    /// Error for a line which ended too early
+    pub fn error_at_end(&self, expected: impl Into<String>) -> ParseError {
+        self.error_at(&self.text[self.text.len()..], expected)
+    }
+
+    /// Parse the whole `token` into a `T`
+    pub fn parse<T: FromStr>(&self, token: &str, expected: &str) -> Result<T, ParseError> {
+        token.parse().map_err(|_| self.error_at(token, expected))
+    }
+
+    /// Removes `prefix` from the start of `token`
+    pub fn strip_prefix<'b>(&self, token: &'b str, prefix: &str) -> Result<&'b str, ParseError> {
+        if let Some(rest) = token.strip_prefix(prefix) {
+            Ok(rest)
+        } else {
+            // Only report as much of the token as the prefix is long
+            let end = token
+                .char_indices()
+                .nth(prefix.chars().count())
+                .map_or(token.len(), |(idx, _)| idx);
+            Err(self.error_at(&token[..end], format!("{:?}", prefix)))
+        }
+    }
+}
+
+/// Iterate over all lines of the input for `day`
+pub fn lines<'a>(day: u8, input: &'a str) -> impl Iterator<Item = Line<'a>> + 'a {
+    input
+        .lines()
+        .enumerate()
+        .map(move |(idx, text)| Line::new(day, idx + 1, text))
+}
+
+#[test]
+fn test_display() {
+    let err = ParseError::new(3, 2, 5, "\"@\"", "\"#\"");
+    assert_eq!(
+        err.to_string(),
+        r##"day 3, line 2, column 5: expected "@", found "#""##
+    );
+}
+
+#[test]
+fn test_empty_input() {
+    assert_eq!(
+        empty_input(6, "a coordinate").to_string(),
+        "day 6, line 1, column 1: expected a coordinate, found end of input"
+    );
+    assert_eq!(
+        NoAnswer::new(3, "every claim overlaps another one").to_string(),
+        "day 3: no answer, every claim overlaps another one"
+    );
+}
+
+#[test]
+fn test_column_of() {
+    let line = Line::new(1, 1, "äb cd");
+    let mut parts = line.text().split(' ');
+    parts.next();
+    let token = parts.next().unwrap();
+    assert_eq!(line.column_of(token), 4);
+    assert_eq!(
+        line.parse::<u32>(token, "a number").unwrap_err(),
+        ParseError::new(1, 1, 4, "a number", "\"cd\"")
+    );
+}
+
+#[test]
+fn test_error_at_end() {
+    let line = Line::new(1, 7, "ab");
+    assert_eq!(
+        line.error_at_end("\",\""),
+        ParseError::new(1, 7, 3, "\",\"", "end of line")
+    );
+}
@@ -0,0 +1,436 @@
+//! Points, vectors, directions, and bounding boxes on the 2D plane of the puzzles
+//!
+//! All types are generic over the coordinate type, such that each day can keep the integer type
+//! of its input.
+
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// Number types which can be used as coordinates
+pub trait Scalar:
+    Copy + Ord + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+        )*
+    };
+}
+
+impl_scalar!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Absolute difference, which also works for unsigned types
+fn abs_diff<T: Scalar>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+/// A position on a 2D plane
+///
+/// The y axis points downwards, as in all the puzzles.
+/// Points are ordered in reading order, i.e., first top to bottom, then left to right.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Scalar> Point<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+
+    /// Sum of the distances along both axis
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// Maximum of the distances along both axis
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+
+    /// Move by one unit into `direction`
+    ///
+    /// For unsigned types this panics (in debug mode) when moving beyond 0.
+    pub fn step(self, direction: Direction) -> Self {
+        match direction {
+            Direction::North => Point::new(self.x, self.y - T::ONE),
+            Direction::East => Point::new(self.x + T::ONE, self.y),
+            Direction::South => Point::new(self.x, self.y + T::ONE),
+            Direction::West => Point::new(self.x - T::ONE, self.y),
+        }
+    }
+}
+
+impl<T: Ord> Ord for Point<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.y.cmp(&other.y).then_with(|| self.x.cmp(&other.x))
+    }
+}
+
+impl<T: Ord> PartialOrd for Point<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(p: Point<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T: Scalar> Add<Vector<T>> for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, v: Vector<T>) -> Self::Output {
+        Point::new(self.x + v.dx, self.y + v.dy)
+    }
+}
+
+impl<T: Scalar> AddAssign<Vector<T>> for Point<T> {
+    fn add_assign(&mut self, v: Vector<T>) {
+        *self = *self + v;
+    }
+}
+
+impl<T: Scalar> Sub<Vector<T>> for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, v: Vector<T>) -> Self::Output {
+        Point::new(self.x - v.dx, self.y - v.dy)
+    }
+}
+
+impl<T: Scalar> SubAssign<Vector<T>> for Point<T> {
+    fn sub_assign(&mut self, v: Vector<T>) {
+        *self = *self - v;
+    }
+}
+
+impl<T: Scalar> Sub for Point<T> {
+    type Output = Vector<T>;
+
+    /// The vector pointing from `other` to `self`
+    fn sub(self, other: Self) -> Self::Output {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+/// The difference between two points, e.g., a velocity
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Vector<T> {
+    pub dx: T,
+    pub dy: T,
+}
+
+impl<T: Scalar> Vector<T> {
+    pub fn new(dx: T, dy: T) -> Self {
+        Vector { dx, dy }
+    }
+
+    pub fn manhattan_length(self) -> T {
+        abs_diff(self.dx, T::ZERO) + abs_diff(self.dy, T::ZERO)
+    }
+
+    pub fn chebyshev_length(self) -> T {
+        abs_diff(self.dx, T::ZERO).max(abs_diff(self.dy, T::ZERO))
+    }
+}
+
+impl<T: Scalar + Neg<Output = T>> Vector<T> {
+    /// Rotate by 90° counter-clockwise, as seen on the screen
+    pub fn rotate_left(self) -> Self {
+        Vector::new(self.dy, -self.dx)
+    }
+
+    /// Rotate by 90° clockwise, as seen on the screen
+    pub fn rotate_right(self) -> Self {
+        Vector::new(-self.dy, self.dx)
+    }
+}
+
+impl<T: Scalar> Add for Vector<T> {
+    type Output = Vector<T>;
+
+    fn add(self, other: Self) -> Self::Output {
+        Vector::new(self.dx + other.dx, self.dy + other.dy)
+    }
+}
+
+impl<T: Scalar> Sub for Vector<T> {
+    type Output = Vector<T>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Vector::new(self.dx - other.dx, self.dy - other.dy)
+    }
+}
+
+impl<T: Scalar> Mul<T> for Vector<T> {
+    type Output = Vector<T>;
+
+    fn mul(self, factor: T) -> Self::Output {
+        Vector::new(self.dx * factor, self.dy * factor)
+    }
+}
+
+impl<T: Scalar + Neg<Output = T>> Neg for Vector<T> {
+    type Output = Vector<T>;
+
+    fn neg(self) -> Self::Output {
+        Vector::new(-self.dx, -self.dy)
+    }
+}
+
+/// One of the four cardinal directions
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn all() -> [Direction; 4] {
+        [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ]
+    }
+
+    /// Turn by 90° counter-clockwise
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    /// Turn by 90° clockwise
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    /// Unit vector pointing into this direction
+    pub fn vector(self) -> Vector<isize> {
+        match self {
+            Direction::North => Vector::new(0, -1),
+            Direction::East => Vector::new(1, 0),
+            Direction::South => Vector::new(0, 1),
+            Direction::West => Vector::new(-1, 0),
+        }
+    }
+}
+
+/// An axis aligned rectangle, where both `min` and `max` are part of the box
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BoundingBox<T> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Scalar> BoundingBox<T> {
+    pub fn new(min: Point<T>, max: Point<T>) -> Self {
+        BoundingBox { min, max }
+    }
+
+    /// Smallest box containing all the points, or `None` if there are no points
+    pub fn from_points<I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Point<T>>,
+    {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bbox = BoundingBox::new(first, first);
+        for p in points {
+            bbox.include(p);
+        }
+        Some(bbox)
+    }
+
+    /// Grow the box such that it contains `point`
+    pub fn include(&mut self, point: Point<T>) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+    }
+
+    /// Grow the box by `amount` into all four directions
+    pub fn expand(self, amount: T) -> Self {
+        BoundingBox::new(
+            Point::new(self.min.x - amount, self.min.y - amount),
+            Point::new(self.max.x + amount, self.max.y + amount),
+        )
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, point: Point<T>) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+    }
+
+    /// True if the point is part of the outermost ring of the box
+    pub fn is_on_edge(&self, point: Point<T>) -> bool {
+        self.contains(point)
+            && (point.x == self.min.x
+                || point.x == self.max.x
+                || point.y == self.min.y
+                || point.y == self.max.y)
+    }
+
+    /// Iterate over all points inside the box in reading order
+    pub fn points(&self) -> Points<T> {
+        Points {
+            bbox: *self,
+            next: Some(self.min),
+        }
+    }
+}
+
+/// Iterator returned by [`BoundingBox::points`]
+#[derive(Clone, Debug)]
+pub struct Points<T> {
+    bbox: BoundingBox<T>,
+    next: Option<Point<T>>,
+}
+
+impl<T: Scalar> Iterator for Points<T> {
+    type Item = Point<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = if current.x < self.bbox.max.x {
+            Some(Point::new(current.x + T::ONE, current.y))
+        } else if current.y < self.bbox.max.y {
+            Some(Point::new(self.bbox.min.x, current.y + T::ONE))
+        } else {
+            None
+        };
+        Some(current)
+    }
+}
+
+#[test]
+fn test_distances() {
+    let a = Point::new(1, 6);
+    let b = Point::new(8, 3);
+    assert_eq!(a.manhattan(b), 10);
+    assert_eq!(a.chebyshev(b), 7);
+    assert_eq!(Point::new(3usize, 5).manhattan(Point::new(5, 3)), 4);
+}
+
+#[test]
+fn test_reading_order() {
+    let mut points = vec![Point::new(2, 0), Point::new(0, 1), Point::new(1, 0)];
+    points.sort();
+    assert_eq!(
+        points,
+        vec![Point::new(1, 0), Point::new(2, 0), Point::new(0, 1)]
+    );
+}
+
+#[test]
+fn test_rotation() {
+    let mut dir = Direction::North;
+    let mut v = dir.vector();
+    for _ in 0..4 {
+        dir = dir.turn_left();
+        v = v.rotate_left();
+        assert_eq!(dir.vector(), v);
+    }
+    assert_eq!(Direction::West.turn_right(), Direction::North);
+    assert_eq!(
+        Direction::East.vector().rotate_right(),
+        Direction::South.vector()
+    );
+    assert_eq!(
+        Point::new(0, 0) + Direction::North.vector() * 3,
+        Point::new(0, -3)
+    );
+}
+
+#[test]
+fn test_bounding_box() {
+    let bbox =
+        BoundingBox::from_points(vec![Point::new(1, 1), Point::new(3, -1), Point::new(2, 0)])
+            .unwrap();
+    assert_eq!(bbox, BoundingBox::new(Point::new(1, -1), Point::new(3, 1)));
+    assert_eq!((bbox.width(), bbox.height(), bbox.area()), (3, 3, 9));
+    assert!(bbox.is_on_edge(Point::new(1, 0)));
+    assert!(!bbox.is_on_edge(Point::new(2, 0)));
+    assert!(!bbox.contains(Point::new(0, 0)));
+    assert_eq!(bbox.expand(1).area(), 25);
+    assert_eq!(
+        bbox.points().take(4).collect::<Vec<_>>(),
+        vec![
+            Point::new(1, -1),
+            Point::new(2, -1),
+            Point::new(3, -1),
+            Point::new(1, 0)
+        ]
+    );
+    assert_eq!(bbox.points().count(), 9);
+}
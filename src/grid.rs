@@ -0,0 +1,356 @@
+//! Rectangular grids of cells, like the fuel cells or the mine cart tracks
+//!
+//! A `Grid` is indexed by `Point`s from the `geometry` module, and its top-left cell can be
+//! anywhere, so the puzzle coordinates work directly.
+
+use error::{self, ParseError};
+use geometry::{Point, Vector};
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+/// Position of a cell within a grid
+pub type Position = Point<isize>;
+
+/// A rectangular 2D grid stored in row-major order
+///
+/// The top-left cell does not have to be at `(0, 0)`, but can be at any `(min_x, min_y)`.
+/// This allows working with the coordinates from the puzzle directly, even if they are negative.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Grid<T> {
+    min_x: isize,
+    min_y: isize,
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Create a grid with the top-left cell at `(0, 0)` and all cells set to `fill`
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self::with_origin(0, 0, width, height, fill)
+    }
+
+    /// Create a grid with the top-left cell at `(min_x, min_y)` and all cells set to `fill`
+    pub fn with_origin(min_x: isize, min_y: isize, width: usize, height: usize, fill: T) -> Self {
+        Self::from_vec(min_x, min_y, width, height, vec![fill; width * height])
+    }
+
+    /// Parse a character map, where each line is one row
+    ///
+    /// `parse_cell` returns `None` if the character is not valid, which is then reported as
+    /// `expected`. Lines shorter than the longest line are padded with `fill`.
+    pub fn parse<F>(
+        day: u8,
+        input: &str,
+        fill: T,
+        expected: &str,
+        mut parse_cell: F,
+    ) -> Result<Self, ParseError>
+    where
+        F: FnMut(Position, char) -> Option<T>,
+    {
+        let rows: Vec<Vec<T>> = error::lines(day, input)
+            .enumerate()
+            .map(|(y, line)| {
+                line.text()
+                    .char_indices()
+                    .enumerate()
+                    .map(|(x, (idx, c))| {
+                        parse_cell(Point::new(x as isize, y as isize), c).ok_or_else(|| {
+                            line.error_at(&line.text()[idx..idx + c.len_utf8()], expected)
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for mut row in rows {
+            let missing = width - row.len();
+            row.extend((0..missing).map(|_| fill.clone()));
+            cells.append(&mut row);
+        }
+        Ok(Self::from_vec(0, 0, width, height, cells))
+    }
+}
+
+impl<T> Grid<T> {
+    /// Create a grid from cells in row-major order
+    ///
+    /// Panics if the number of cells does not match the size.
+    pub fn from_vec(
+        min_x: isize,
+        min_y: isize,
+        width: usize,
+        height: usize,
+        cells: Vec<T>,
+    ) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "The number of cells must match the grid size"
+        );
+        Grid {
+            min_x,
+            min_y,
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Create a grid by calculating each cell from its position
+    pub fn from_fn<F>(min_x: isize, min_y: isize, width: usize, height: usize, mut f: F) -> Self
+    where
+        F: FnMut(Position) -> T,
+    {
+        let cells = (0..height as isize)
+            .flat_map(|y| (0..width as isize).map(move |x| Point::new(min_x + x, min_y + y)))
+            .map(&mut f)
+            .collect();
+        Self::from_vec(min_x, min_y, width, height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn min_x(&self) -> isize {
+        self.min_x
+    }
+
+    pub fn min_y(&self) -> isize {
+        self.min_y
+    }
+
+    /// Largest x coordinate still inside the grid
+    pub fn max_x(&self) -> isize {
+        self.min_x + self.width as isize - 1
+    }
+
+    /// Largest y coordinate still inside the grid
+    pub fn max_y(&self) -> isize {
+        self.min_y + self.height as isize - 1
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        pos.x >= self.min_x && pos.x <= self.max_x() && pos.y >= self.min_y && pos.y <= self.max_y()
+    }
+
+    fn index_of(&self, pos: Position) -> Option<usize> {
+        if self.contains(pos) {
+            Some((pos.x - self.min_x) as usize + (pos.y - self.min_y) as usize * self.width)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        self.index_of(pos).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        match self.index_of(pos) {
+            Some(idx) => Some(&mut self.cells[idx]),
+            None => None,
+        }
+    }
+
+    /// Set the cell at `pos` and return the previous value
+    ///
+    /// Returns `None` and leaves the grid unchanged if `pos` is outside of the grid.
+    pub fn set(&mut self, pos: Position, value: T) -> Option<T> {
+        self.get_mut(pos)
+            .map(|cell| ::std::mem::replace(cell, value))
+    }
+
+    /// Iterate over all positions in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let (min_x, min_y, width, height) = (self.min_x, self.min_y, self.width, self.height);
+        (0..height as isize)
+            .flat_map(move |y| (0..width as isize).map(move |x| Point::new(min_x + x, min_y + y)))
+    }
+
+    /// Iterate over all positions and cells in row-major order
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (Position, &'a T)> + 'a {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Iterate over all rows from top to bottom
+    pub fn rows<'a>(&'a self) -> impl Iterator<Item = &'a [T]> + 'a {
+        // `chunks` panics for a size of 0
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Iterate over all cells in row `y` from left to right
+    ///
+    /// The iterator is empty if the row is outside of the grid.
+    pub fn row<'a>(&'a self, y: isize) -> impl Iterator<Item = &'a T> + 'a {
+        let start = self.index_of(Point::new(self.min_x, y));
+        let width = if start.is_some() { self.width } else { 0 };
+        let start = start.unwrap_or(0);
+        self.cells[start..start + width].iter()
+    }
+
+    /// Iterate over all cells in column `x` from top to bottom
+    ///
+    /// The iterator is empty if the column is outside of the grid.
+    pub fn column<'a>(&'a self, x: isize) -> impl Iterator<Item = &'a T> + 'a {
+        let start = self.index_of(Point::new(x, self.min_y));
+        let height = if start.is_some() { self.height } else { 0 };
+        let start = start.unwrap_or(0);
+        (0..height).map(move |row| &self.cells[start + row * self.width])
+    }
+
+    /// Positions of the direct neighbours in the 4 cardinal directions, which are inside of the grid
+    pub fn neighbours4<'a>(&'a self, pos: Position) -> impl Iterator<Item = Position> + 'a {
+        [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .map(move |&(dx, dy)| pos + Vector::new(dx, dy))
+            .filter(move |&pos| self.contains(pos))
+    }
+
+    /// Positions of all 8 surrounding neighbours, including diagonals, which are inside of the grid
+    pub fn neighbours8<'a>(&'a self, pos: Position) -> impl Iterator<Item = Position> + 'a {
+        [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+        ]
+        .iter()
+        .map(move |&(dx, dy)| pos + Vector::new(dx, dy))
+        .filter(move |&pos| self.contains(pos))
+    }
+
+    /// Create a new grid of the same size and origin by mapping each cell
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid::from_vec(
+            self.min_x,
+            self.min_y,
+            self.width,
+            self.height,
+            self.cells.iter().map(f).collect(),
+        )
+    }
+
+    /// Render the grid by converting each cell into a single character
+    ///
+    /// Rows are separated by newlines, there is no newline after the last row.
+    pub fn display<'a, F>(&'a self, to_char: F) -> GridDisplay<'a, T, F>
+    where
+        F: Fn(&T) -> char,
+    {
+        GridDisplay {
+            grid: self,
+            to_char,
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &Self::Output {
+        match self.index_of(pos) {
+            Some(idx) => &self.cells[idx],
+            None => panic!("Position {:?} is outside of the grid", pos),
+        }
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut Self::Output {
+        match self.index_of(pos) {
+            Some(idx) => &mut self.cells[idx],
+            None => panic!("Position {:?} is outside of the grid", pos),
+        }
+    }
+}
+
+/// Helper returned by [`Grid::display`]
+pub struct GridDisplay<'a, T: 'a, F> {
+    grid: &'a Grid<T>,
+    to_char: F,
+}
+
+impl<'a, T, F> Display for GridDisplay<'a, T, F>
+where
+    F: Fn(&T) -> char,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (y, row) in self.grid.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", (self.to_char)(cell))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_origin() {
+    let mut grid = Grid::with_origin(-2, -1, 4, 3, 0);
+    assert_eq!((grid.max_x(), grid.max_y()), (1, 1));
+    assert_eq!(grid.set(Point::new(-2, -1), 5), Some(0));
+    assert_eq!(grid.set(Point::new(2, 0), 5), None);
+    grid[Point::new(1, 1)] = 7;
+    assert_eq!(grid.get(Point::new(-2, -1)), Some(&5));
+    assert_eq!(grid.get(Point::new(-3, -1)), None);
+    assert_eq!(grid.row(1).cloned().collect::<Vec<_>>(), vec![0, 0, 0, 7]);
+    assert_eq!(grid.column(-2).cloned().collect::<Vec<_>>(), vec![5, 0, 0]);
+    assert_eq!(grid.row(2).count(), 0);
+}
+
+#[test]
+fn test_neighbours() {
+    let grid = Grid::new(3, 3, ());
+    assert_eq!(
+        grid.neighbours4(Point::new(0, 0)).collect::<Vec<_>>(),
+        vec![Point::new(1, 0), Point::new(0, 1)]
+    );
+    assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+    assert_eq!(grid.neighbours8(Point::new(2, 2)).count(), 3);
+}
+
+#[test]
+fn test_parse_and_display() {
+    let grid = Grid::parse(0, "#.#\n.#", false, "'#' or '.'", |_, c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+    .unwrap();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(
+        grid.display(|&c| if c { '#' } else { '.' }).to_string(),
+        "#.#\n.#."
+    );
+
+    let err = Grid::parse(0, "#.\n.x", false, "'#' or '.'", |_, c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+    .unwrap_err();
+    assert_eq!(err, ParseError::new(0, 2, 2, "'#' or '.'", "\"x\""));
+}
@@ -0,0 +1,70 @@
+//! Normalization of the puzzle input before parsing
+//!
+//! Inputs saved by some editors start with a byte order mark, use `\r\n` line endings or have
+//! trailing whitespace. The parsers only ever see the normalized form.
+
+use std::borrow::Cow;
+
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// Strip a byte order mark, use `\n` line endings and remove trailing whitespace
+///
+/// Whitespace is removed from the end of every line and empty lines are removed from the end of
+/// the input. Leading whitespace is kept, as it is part of the tracks of day 13. Input which is
+/// already normalized is not copied.
+pub fn normalize<'a>(input: &'a str) -> Cow<'a, str> {
+    let input = input.trim_start_matches(BYTE_ORDER_MARK);
+    if is_normalized(input) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut normalized = String::with_capacity(input.len());
+    // Old Mac line endings are a single `\r`
+    for line in input.replace("\r\n", "\n").split(&['\n', '\r'][..]) {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    let len = normalized.trim_end_matches('\n').len();
+    normalized.truncate(len);
+    Cow::Owned(normalized)
+}
+
+fn is_normalized(input: &str) -> bool {
+    !input.contains('\r')
+        && !input.ends_with('\n')
+        && input
+            .split('\n')
+            .all(|line| line.trim_end().len() == line.len())
+}
+
+/// Turn `input` into the untidy form some editors save: with a byte order mark, `\r\n` line
+/// endings and trailing whitespace
+#[cfg(test)]
+pub fn untidy(input: &str) -> String {
+    let lines: Vec<_> = input.lines().map(|line| format!("{} \t", line)).collect();
+    format!("{}{}\r\n\r\n", BYTE_ORDER_MARK, lines.join("\r\n"))
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(
+        normalize("\u{feff}a \r\n  b\t\r\nc\rd\r\n\r\n"),
+        "a\n  b\nc\nd"
+    );
+    assert_eq!(normalize("a\n\n b \n"), "a\n\n b");
+    assert_eq!(normalize(""), "");
+    assert_eq!(normalize(" \r\n"), "");
+}
+
+#[test]
+fn test_normalize_borrows() {
+    match normalize("\u{feff}a\n  b") {
+        Cow::Borrowed(input) => assert_eq!(input, "a\n  b"),
+        Cow::Owned(_) => panic!("Normalized input must not be copied"),
+    }
+}
+
+#[test]
+fn test_untidy() {
+    assert_eq!(normalize(&untidy("a\n b")), "a\n b");
+}
@@ -0,0 +1,83 @@
+//! Minimal JSON writer for the machine-readable output of the command line interface
+
+use std::fmt::{self, Display, Write};
+
+/// Quote and escape a string as a JSON string literal
+pub fn string(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(res, "\\u{:04x}", c as u32);
+            }
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+/// A JSON object whose fields are written in insertion order
+#[derive(Clone, Default, Debug)]
+pub struct Object {
+    /// Field name and the already encoded value
+    fields: Vec<(String, String)>,
+}
+
+impl Object {
+    pub fn new() -> Self {
+        Object::default()
+    }
+
+    /// Add a field with an already encoded JSON value
+    pub fn raw(mut self, name: &str, json: String) -> Self {
+        self.fields.push((name.to_string(), json));
+        self
+    }
+
+    pub fn string(self, name: &str, value: &str) -> Self {
+        self.raw(name, string(value))
+    }
+
+    /// Add a number or boolean, which are written using their `Display` implementation
+    pub fn number<T: Display>(self, name: &str, value: T) -> Self {
+        self.raw(name, value.to_string())
+    }
+
+    pub fn null(self, name: &str) -> Self {
+        self.raw(name, "null".to_string())
+    }
+}
+
+impl Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.write_str("{")?;
+        for (idx, (name, value)) in self.fields.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}:{}", string(name), value)?;
+        }
+        f.write_str("}")
+    }
+}
+
+#[test]
+fn test_object() {
+    let obj = Object::new()
+        .number("day", 10)
+        .string("answer", "a \"b\"\n\\c\u{1}")
+        .number("passed", true)
+        .null("error")
+        .raw("point", Object::new().number("x", -1).to_string());
+    assert_eq!(
+        obj.to_string(),
+        r#"{"day":10,"answer":"a \"b\"\n\\c\u0001","passed":true,"error":null,"point":{"x":-1}}"#
+    );
+}
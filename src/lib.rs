@@ -1,29 +1,92 @@
+//! Solutions for the Advent of Code 2018
+//!
+//! Every day is a module `dayNN` behind a cargo feature of the same name.
+//! Besides the `solve_part1` and `solve_part2` functions used by `cargo aoc`, the modules expose
+//! the parsed input types, the core algorithms with their parameters, and the simulation states.
+//! The `generator` functions parse the puzzle input into those types, after `input::normalize`
+//! removed byte order marks, `\r\n` line endings and trailing whitespace.
+//! Line based inputs are read from left to right with a `parse::Cursor`.
+//!
+//! Solvers can be listed and run by day and part through the `registry`.
+//! Step driven puzzles implement the `simulation::Simulation` trait.
+//! Long running solvers report their progress to an `observer::Observer`.
+//! The `viewer` steps through simulations and renders them in a terminal.
+//! With the `serialize` feature the models derive serde's traits and simulations can be saved and
+//! resumed through `snapshot::Snapshot`.
+//! Random inputs of any size can be created with `random_input`.
+
 #![feature(const_str_len, dbg_macro)]
 
 extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 extern crate hashbrown;
+#[cfg(feature = "day09")]
 #[macro_use]
 extern crate intrusive_collections;
 extern crate misc_utils;
 #[cfg(test)]
 extern crate pretty_assertions;
 extern crate rayon;
+#[cfg(feature = "serialize")]
+extern crate serde;
+#[cfg(feature = "serialize")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "serialize")]
+extern crate serde_json;
+
+pub mod answer;
+pub mod budget;
+pub mod error;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod observer;
+#[cfg(test)]
+mod oracle;
+#[macro_use]
+pub mod params;
+pub mod parse;
+#[cfg(test)]
+mod property;
+pub mod random_input;
+pub mod registry;
+pub mod regression;
+pub mod rng;
+pub mod simulation;
+#[cfg(feature = "serialize")]
+pub mod snapshot;
+pub mod viewer;
 
-// pub mod day01;
-// pub mod day02;
-// pub mod day03;
-// pub mod day04;
-// pub mod day05;
-// pub mod day06;
-// pub mod day07;
-// pub mod day08;
-// pub mod day09;
-// pub mod day10;
-// pub mod day11;
-// pub mod day12;
+#[cfg(feature = "day01")]
+pub mod day01;
+#[cfg(feature = "day02")]
+pub mod day02;
+#[cfg(feature = "day03")]
+pub mod day03;
+#[cfg(feature = "day04")]
+pub mod day04;
+#[cfg(feature = "day05")]
+pub mod day05;
+#[cfg(feature = "day06")]
+pub mod day06;
+#[cfg(feature = "day07")]
+pub mod day07;
+#[cfg(feature = "day08")]
+pub mod day08;
+#[cfg(feature = "day09")]
+pub mod day09;
+#[cfg(feature = "day10")]
+pub mod day10;
+#[cfg(feature = "day11")]
+pub mod day11;
+#[cfg(feature = "day12")]
+pub mod day12;
+#[cfg(feature = "day13")]
 pub mod day13;
+#[cfg(feature = "day14")]
 pub mod day14;
 
 aoc_lib! { year = 2018 }
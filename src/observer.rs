@@ -0,0 +1,116 @@
+//! Progress reports from long running solvers
+//!
+//! Solvers which take many steps report their current state to an `Observer` every few steps.
+//! Tests use this to check intermediate states, the command line interface to show the progress.
+//! Closures taking the number of steps and the state are observers, which see every step.
+
+/// Receives the state of a solver every `interval` steps
+pub trait Observer<S: ?Sized> {
+    /// Number of steps between two calls of `observe`
+    fn interval(&self) -> usize {
+        1
+    }
+
+    /// Called with the state after `steps` steps, for `steps` being a multiple of `interval`
+    fn observe(&mut self, steps: usize, state: &S);
+}
+
+impl<S: ?Sized, F: FnMut(usize, &S)> Observer<S> for F {
+    fn observe(&mut self, steps: usize, state: &S) {
+        self(steps, state)
+    }
+}
+
+/// Ignores all reports
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Silent;
+
+impl<S: ?Sized> Observer<S> for Silent {
+    fn interval(&self) -> usize {
+        usize::MAX
+    }
+
+    fn observe(&mut self, _steps: usize, _state: &S) {}
+}
+
+/// A short description of a state, like the number of carts left on the tracks
+pub trait Summary {
+    fn summary(&self) -> String;
+}
+
+/// Receives the number of steps and the summary of the state
+pub type Callback<'a> = &'a mut dyn FnMut(usize, &str);
+
+/// Observer for any state with a `Summary`, which passes the steps and the summary on
+///
+/// This hides the type of the state, such that the same reporter works for all solvers.
+pub struct Reporter<'a> {
+    interval: usize,
+    callback: Option<Callback<'a>>,
+}
+
+impl<'a> Reporter<'a> {
+    /// Call `callback` every `interval` steps, an `interval` of 0 is treated as 1
+    pub fn new(interval: usize, callback: Callback<'a>) -> Self {
+        Reporter {
+            interval: interval.max(1),
+            callback: Some(callback),
+        }
+    }
+
+    /// A reporter which ignores all reports
+    pub fn silent() -> Self {
+        Reporter {
+            interval: usize::MAX,
+            callback: None,
+        }
+    }
+}
+
+impl<'a, S: Summary + ?Sized> Observer<S> for Reporter<'a> {
+    fn interval(&self) -> usize {
+        self.interval
+    }
+
+    fn observe(&mut self, steps: usize, state: &S) {
+        if let Some(ref mut callback) = self.callback {
+            callback(steps, &state.summary());
+        }
+    }
+}
+
+#[cfg(test)]
+struct Counter(usize);
+
+#[cfg(test)]
+impl Summary for Counter {
+    fn summary(&self) -> String {
+        format!("at {}", self.0)
+    }
+}
+
+#[test]
+fn test_reporter() {
+    let mut reports = Vec::new();
+    {
+        let mut callback = |steps: usize, summary: &str| reports.push((steps, summary.to_string()));
+        let mut reporter = Reporter::new(0, &mut callback);
+        assert_eq!(Observer::<Counter>::interval(&reporter), 1);
+        reporter.observe(3, &Counter(7));
+    }
+    assert_eq!(reports, vec![(3, "at 7".to_string())]);
+
+    let mut silent = Reporter::silent();
+    silent.observe(3, &Counter(7));
+}
+
+#[test]
+fn test_closure_observer() {
+    let mut seen = Vec::new();
+    {
+        let mut observer = |steps: usize, state: &Counter| seen.push((steps, state.0));
+        assert_eq!(Observer::<Counter>::interval(&observer), 1);
+        observer.observe(1, &Counter(2));
+    }
+    assert_eq!(seen, vec![(1, 2)]);
+}
@@ -0,0 +1,81 @@
+//! Day 1: Chronal Calibration
+
+use day01;
+use property;
+use rng::Rng;
+
+pub fn part1(changes: &[i32]) -> i32 {
+    let mut frequency = 0;
+    for change in changes {
+        frequency += change;
+    }
+    frequency
+}
+
+/// First frequency reached twice, counting the start at 0, within the first `passes` passes
+pub fn part2(changes: &[i32], passes: usize) -> Option<i32> {
+    first_repeat(changes, passes).map(|(frequency, _, _)| frequency)
+}
+
+/// Like `part2`, but also return the pass and the index of the change reaching the frequency
+pub fn first_repeat(changes: &[i32], passes: usize) -> Option<(i32, usize, usize)> {
+    let mut frequency = 0;
+    let mut seen = vec![frequency];
+    for pass in 0..passes {
+        for (index, change) in changes.iter().enumerate() {
+            frequency += change;
+            if seen.contains(&frequency) {
+                return Some((frequency, pass, index));
+            }
+            seen.push(frequency);
+        }
+    }
+    None
+}
+
+fn random_changes(rng: &mut Rng) -> Vec<i32> {
+    let len = rng.range(1, 20);
+    (0..len).map(|_| rng.range(-10, 10) as i32).collect()
+}
+
+#[test]
+fn test_part1() {
+    property::check("day01 part1", 100, random_changes, |changes| {
+        property::equal(day01::solve_part1(changes), part1(changes))
+    });
+}
+
+#[test]
+fn test_part2() {
+    property::check("day01 part2", 200, random_changes, |changes| {
+        match part2(changes, 100) {
+            Some(expected) => property::equal(day01::solve_part2(changes), Ok(expected)),
+            None => Ok(()),
+        }
+    });
+}
+
+#[test]
+fn test_first_repeat() {
+    property::check("day01 first_repeat", 200, random_changes, |changes| {
+        let repeat = day01::first_repeat(changes);
+        match first_repeat(changes, 100) {
+            Some((frequency, pass, index)) => {
+                let expected = day01::Repeat {
+                    frequency: i64::from(frequency),
+                    pass: pass as u64,
+                    index,
+                };
+                property::equal(repeat, Some(expected))
+            }
+            // Repeats after more than 100 passes are not checked, except that they need a drift
+            None => match repeat {
+                Some(repeat) if day01::solve_part1(changes) == 0 => Err(format!(
+                    "Without drift the repeat must be in the first pass, got {:?}",
+                    repeat
+                )),
+                _ => Ok(()),
+            },
+        }
+    });
+}
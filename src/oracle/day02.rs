@@ -0,0 +1,159 @@
+//! Day 2: Inventory Management System
+
+use day02;
+use property;
+use rng::Rng;
+
+/// Number of ids with a letter appearing exactly twice, times those with one appearing thrice
+pub fn part1(ids: &[Vec<char>]) -> u32 {
+    let has_letter_times = |id: &Vec<char>, times| {
+        id.iter()
+            .any(|letter| id.iter().filter(|&other| other == letter).count() == times)
+    };
+    let twos = ids.iter().filter(|id| has_letter_times(id, 2)).count() as u32;
+    let threes = ids.iter().filter(|id| has_letter_times(id, 3)).count() as u32;
+    twos * threes
+}
+
+/// Common letters of the first pair of ids which differ in exactly one position
+///
+/// Pairs are ordered by their later id, then by their earlier id.
+pub fn part2(ids: &[Vec<char>]) -> Option<String> {
+    for (idx, id) in ids.iter().enumerate() {
+        for other in &ids[..idx] {
+            let common: String = id
+                .iter()
+                .zip(other)
+                .filter(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect();
+            if common.len() + 1 == id.len() {
+                return Some(common);
+            }
+        }
+    }
+    None
+}
+
+/// Indices and common letters of all pairs of ids which differ in exactly one position
+///
+/// Pairs are ordered by their later id, then by their earlier id.
+pub fn similar_pairs(ids: &[Vec<char>]) -> Vec<(usize, usize, String)> {
+    let mut pairs = Vec::new();
+    for (idx, id) in ids.iter().enumerate() {
+        for (other_idx, other) in ids[..idx].iter().enumerate() {
+            let differences = id.iter().zip(other).filter(|(a, b)| a != b).count();
+            if id.len() == other.len() && differences == 1 {
+                let common = id.iter().zip(other).filter(|(a, b)| a == b).map(|(a, _)| a);
+                pairs.push((other_idx, idx, common.collect()));
+            }
+        }
+    }
+    pairs
+}
+
+/// Short ids from few letters, such that there are many similar ones
+fn random_ids(rng: &mut Rng) -> Vec<Vec<char>> {
+    let count = rng.range(2, 10);
+    (0..count)
+        .map(|_| (0..4).map(|_| *rng.choose(&['a', 'b', 'c'])).collect())
+        .collect()
+}
+
+/// Ids of different lengths, which are close to each other in both metrics
+fn random_words(rng: &mut Rng) -> (Vec<Vec<char>>, Vec<char>, usize) {
+    let word = |rng: &mut Rng| -> Vec<char> {
+        let len = rng.range(0, 6);
+        (0..len).map(|_| *rng.choose(&['a', 'b', 'c'])).collect()
+    };
+    let count = rng.range(0, 12);
+    let words = (0..count).map(|_| word(rng)).collect();
+    (words, word(rng), rng.range(0, 4) as usize)
+}
+
+/// Distance between two words by the definition, the smallest number of edits
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    match (a.split_first(), b.split_first()) {
+        (None, _) => b.len(),
+        (_, None) => a.len(),
+        (Some((ca, ra)), Some((cb, rb))) => {
+            let replace = levenshtein(ra, rb) + if ca == cb { 0 } else { 1 };
+            replace
+                .min(levenshtein(ra, b) + 1)
+                .min(levenshtein(a, rb) + 1)
+        }
+    }
+}
+
+fn to_input(ids: &[Vec<char>]) -> String {
+    let lines: Vec<String> = ids.iter().map(|id| id.iter().collect()).collect();
+    lines.join("\n")
+}
+
+#[test]
+fn test_part1() {
+    property::check("day02 part1", 200, random_ids, |ids| {
+        property::equal(day02::solve_part1(&to_input(ids)), part1(ids))
+    });
+}
+
+#[test]
+fn test_part2() {
+    property::check("day02 part2", 200, random_ids, |ids| {
+        // The puzzle ids all have the same length
+        if ids.iter().any(|id| id.len() != ids[0].len()) {
+            return Ok(());
+        }
+        let input = to_input(ids);
+        property::equal(
+            day02::solve_part2_pairwise(&input),
+            day02::solve_part2(&input),
+        )?;
+        match part2(ids) {
+            Some(expected) => property::equal(day02::solve_part2(&input), Ok(expected)),
+            None => property::equal(day02::solve_part2(&input).is_err(), true),
+        }
+    });
+}
+
+#[test]
+fn test_similar_pairs() {
+    property::check("day02 similar_pairs", 200, random_ids, |ids| {
+        let lines: Vec<String> = ids.iter().map(|id| id.iter().collect()).collect();
+        let pairs: Vec<_> = day02::similar_pairs(&lines)
+            .into_iter()
+            .map(|pair| (pair.first, pair.second, pair.common))
+            .collect();
+        property::equal(pairs, similar_pairs(ids))
+    });
+}
+
+#[test]
+fn test_bk_tree() {
+    property::check("day02 bk_tree", 200, random_words, |(words, query, k)| {
+        let lines: Vec<String> = words.iter().map(|id| id.iter().collect()).collect();
+        let query_line: String = query.iter().collect();
+        for &metric in &[day02::Metric::Hamming, day02::Metric::Levenshtein] {
+            let distance = |a: &[char], b: &[char]| match metric {
+                day02::Metric::Hamming => {
+                    let common = a.iter().zip(b).filter(|(x, y)| x == y).count();
+                    a.len().max(b.len()) - common
+                }
+                day02::Metric::Levenshtein => levenshtein(a, b),
+            };
+            let mut expected: Vec<_> = words
+                .iter()
+                .enumerate()
+                .map(|(index, word)| day02::Neighbour {
+                    distance: distance(word, query),
+                    index,
+                })
+                .filter(|n| n.distance <= *k)
+                .collect();
+            expected.sort();
+            let tree = day02::BkTree::new(&lines, metric);
+            property::equal(tree.within(&query_line, *k), expected)?;
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,93 @@
+//! Day 3: No Matter How You Slice It
+
+use day03::{self, Claim};
+use property;
+use rng::Rng;
+
+/// Number of claims covering each square inch, on a fabric just large enough for all claims
+fn fabric(claims: &[Claim]) -> Vec<Vec<u32>> {
+    let size = claims
+        .iter()
+        .map(|claim| (claim.x + claim.width).max(claim.y + claim.height))
+        .max()
+        .unwrap_or(0) as usize;
+    let mut fabric = vec![vec![0; size]; size];
+    for claim in claims {
+        for x in claim.x..claim.x + claim.width {
+            for y in claim.y..claim.y + claim.height {
+                fabric[y as usize][x as usize] += 1;
+            }
+        }
+    }
+    fabric
+}
+
+/// Number of square inches within two or more claims
+pub fn part1(claims: &[Claim]) -> usize {
+    fabric(claims)
+        .iter()
+        .flat_map(|row| row.iter())
+        .filter(|&&count| count > 1)
+        .count()
+}
+
+/// Id of the first claim, which does not overlap with any other
+pub fn part2(claims: &[Claim]) -> Option<u32> {
+    let fabric = fabric(claims);
+    claims
+        .iter()
+        .find(|claim| {
+            (claim.x..claim.x + claim.width).all(|x| {
+                (claim.y..claim.y + claim.height).all(|y| fabric[y as usize][x as usize] == 1)
+            })
+        })
+        .map(|claim| claim.id)
+}
+
+/// Claims as (x, y, width, height)
+fn random_claims(rng: &mut Rng) -> Vec<(u32, u32, u32, u32)> {
+    let count = rng.range(1, 15);
+    (0..count)
+        .map(|_| {
+            (
+                rng.range(0, 20) as u32,
+                rng.range(0, 20) as u32,
+                rng.range(1, 8) as u32,
+                rng.range(1, 8) as u32,
+            )
+        })
+        .collect()
+}
+
+fn to_claims(claims: &[(u32, u32, u32, u32)]) -> Vec<Claim> {
+    claims
+        .iter()
+        .enumerate()
+        .map(|(idx, &(x, y, width, height))| Claim {
+            id: idx as u32 + 1,
+            x,
+            y,
+            width,
+            height,
+        })
+        .collect()
+}
+
+#[test]
+fn test_part1() {
+    property::check("day03 part1", 200, random_claims, |claims| {
+        let claims = to_claims(claims);
+        property::equal(day03::solve_part1(&claims), part1(&claims))
+    });
+}
+
+#[test]
+fn test_part2() {
+    property::check("day03 part2", 200, random_claims, |claims| {
+        let claims = to_claims(claims);
+        match part2(&claims) {
+            Some(expected) => property::equal(day03::solve_part2(&claims), Ok(expected)),
+            None => property::equal(day03::solve_part2(&claims).is_err(), true),
+        }
+    });
+}
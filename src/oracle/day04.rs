@@ -0,0 +1,144 @@
+//! Day 4: Repose Record
+
+use day04::{self, Event, Record};
+use property;
+use rng::Rng;
+use std::collections::BTreeMap;
+
+/// How often each guard was asleep during each minute, walking through the log minute by minute
+fn sleep_table(records: &[Record]) -> BTreeMap<u16, [u32; 60]> {
+    let mut table = BTreeMap::new();
+    let mut guard = None;
+    let mut asleep_since = None;
+    for record in records {
+        match record.event {
+            Event::BeginsShift(id) => guard = Some(id),
+            Event::FallsAsleep => asleep_since = Some(record.minute),
+            Event::WakesUp => {
+                let since = asleep_since.take().expect("Woke up without sleeping");
+                let minutes = table
+                    .entry(guard.expect("Slept before any shift"))
+                    .or_insert([0; 60]);
+                for minute in since..record.minute {
+                    minutes[minute as usize] += 1;
+                }
+            }
+        }
+    }
+    table
+}
+
+/// The item with the largest key, or `None` if there is no single one
+fn unique_max<T, K, I, F>(items: I, key: F) -> Option<T>
+where
+    I: IntoIterator<Item = T>,
+    K: Ord + Copy,
+    F: Fn(&T) -> K,
+{
+    let items: Vec<_> = items.into_iter().collect();
+    let max = items.iter().map(&key).max()?;
+    let mut best = items.into_iter().filter(|item| key(item) == max);
+    let first = best.next();
+    if best.next().is_some() {
+        None
+    } else {
+        first
+    }
+}
+
+/// Guard with the most minutes asleep, times the minute they slept most often
+///
+/// Returns `None`, if the puzzle is ambiguous.
+pub fn part1(records: &[Record]) -> Option<u32> {
+    let table = sleep_table(records);
+    let (guard, minutes) = unique_max(table, |(_, minutes)| minutes.iter().sum::<u32>())?;
+    let minute = unique_max(0..60, |&minute| minutes[minute as usize])?;
+    Some(u32::from(guard) * minute)
+}
+
+/// Guard which slept most often during the same minute, times that minute
+///
+/// Returns `None`, if the puzzle is ambiguous.
+pub fn part2(records: &[Record]) -> Option<u32> {
+    let table = sleep_table(records);
+    let asleep = table
+        .iter()
+        .flat_map(|(&guard, minutes)| (0..60).map(move |minute| (guard, minute, minutes[minute])));
+    let (guard, minute, _) = unique_max(asleep, |&(_, _, count)| count)?;
+    Some(u32::from(guard) * minute as u32)
+}
+
+/// Shifts as guard id and the minutes at which the guard falls asleep or wakes up
+fn random_shifts(rng: &mut Rng) -> Vec<(u16, Vec<u8>)> {
+    let count = rng.range(1, 8);
+    (0..count)
+        .map(|_| {
+            let guard = rng.range(0, 4) as u16;
+            let changes = rng.range(0, 6);
+            let minutes = (0..changes).map(|_| rng.range(0, 59) as u8).collect();
+            (guard, minutes)
+        })
+        .collect()
+}
+
+/// The log in chronological order, minutes are made distinct and paired up to naps
+fn to_records(shifts: &[(u16, Vec<u8>)]) -> Vec<Record> {
+    let mut records = Vec::new();
+    for (guard, minutes) in shifts {
+        records.push(Record {
+            minute: 0,
+            event: Event::BeginsShift(*guard),
+        });
+        let mut minutes = minutes.clone();
+        minutes.sort();
+        minutes.dedup();
+        for nap in minutes.chunks(2).filter(|nap| nap.len() == 2) {
+            records.push(Record {
+                minute: nap[0],
+                event: Event::FallsAsleep,
+            });
+            records.push(Record {
+                minute: nap[1],
+                event: Event::WakesUp,
+            });
+        }
+    }
+    records
+}
+
+#[test]
+fn test_part1() {
+    property::check("day04 part1", 300, random_shifts, |shifts| {
+        let records = to_records(shifts);
+        match part1(&records) {
+            Some(expected) => {
+                property::equal(day04::solve_part1(&records), Ok(expected))?;
+                property::equal(day04::solve_part1_vec(&records), Ok(expected))
+            }
+            // Without any naps, there is no answer instead of an ambiguous one
+            None if sleep_table(&records).is_empty() => {
+                property::equal(day04::solve_part1(&records).is_err(), true)?;
+                property::equal(day04::solve_part1_vec(&records).is_err(), true)
+            }
+            None => Ok(()),
+        }
+    });
+}
+
+#[test]
+fn test_part2() {
+    property::check("day04 part2", 300, random_shifts, |shifts| {
+        let records = to_records(shifts);
+        match part2(&records) {
+            Some(expected) => {
+                property::equal(day04::solve_part2(&records), Ok(expected))?;
+                property::equal(day04::solve_part2_vec(&records), Ok(expected))
+            }
+            None if sleep_table(&records).is_empty() => {
+                property::equal(day04::solve_part2(&records).is_err(), true)?;
+                property::equal(day04::solve_part2_vec(&records).is_err(), true)
+            }
+            None => Ok(()),
+        }
+    });
+}
@@ -0,0 +1,76 @@
+//! Day 5: Alchemical Reduction
+
+use day05;
+use property;
+use rng::Rng;
+
+fn reacts(a: char, b: char) -> bool {
+    a != b && a.eq_ignore_ascii_case(&b)
+}
+
+/// Remove the first reacting pair, until there is none left
+pub fn react(polymer: &[char]) -> Vec<char> {
+    let mut polymer = polymer.to_vec();
+    while let Some(idx) = (1..polymer.len()).find(|&idx| reacts(polymer[idx - 1], polymer[idx])) {
+        polymer.drain(idx - 1..=idx);
+    }
+    polymer
+}
+
+pub fn part1(polymer: &[char]) -> usize {
+    react(polymer).len()
+}
+
+/// Shortest polymer after removing all units of one type
+pub fn part2(polymer: &[char]) -> usize {
+    (b'a'..=b'z')
+        .map(|unit| {
+            let remaining: Vec<_> = polymer
+                .iter()
+                .cloned()
+                .filter(|c| c.to_ascii_lowercase() != unit as char)
+                .collect();
+            react(&remaining).len()
+        })
+        .min()
+        .unwrap()
+}
+
+/// Polymers with few unit types, such that many units react
+fn random_polymer(rng: &mut Rng) -> Vec<char> {
+    let len = rng.range(0, 40);
+    (0..len)
+        .map(|_| *rng.choose(&['a', 'A', 'b', 'B', 'c', 'C']))
+        .collect()
+}
+
+#[test]
+fn test_part1() {
+    property::check("day05 part1", 200, random_polymer, |polymer| {
+        let input: String = polymer.iter().collect();
+        property::equal(day05::solve_part1(&input), part1(polymer))
+    });
+}
+
+#[test]
+fn test_part2() {
+    type Solver = fn(&str) -> usize;
+    let variants: [(&str, Solver); 5] = [
+        ("default", day05::solve_part2),
+        ("iterator", day05::solve_part2_iterator),
+        ("rayon", day05::solve_part2_par),
+        ("early_collapse", day05::solve_part2_early_collapse),
+        (
+            "rayon_early_collapse",
+            day05::solve_part2_par_early_collapse,
+        ),
+    ];
+    property::check("day05 part2", 200, random_polymer, |polymer| {
+        let input: String = polymer.iter().collect();
+        let expected = part2(polymer);
+        for (name, solve) in &variants {
+            property::equal(solve(&input), expected).map_err(|err| format!("{}: {}", name, err))?;
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,133 @@
+//! Day 6: Chronal Coordinates
+
+use day06::{self, Coordinate, Params};
+use geometry::Point;
+use property;
+use rng::Rng;
+
+fn manhattan((x1, y1): (i32, i32), (x2, y2): (i32, i32)) -> i32 {
+    (x1 - x2).abs() + (y1 - y2).abs()
+}
+
+/// Index of the single closest coordinate, `None` if there are several
+fn closest(coordinates: &[(i32, i32)], point: (i32, i32)) -> Option<usize> {
+    let distances: Vec<_> = coordinates.iter().map(|&c| manhattan(c, point)).collect();
+    let min = *distances.iter().min()?;
+    let closest: Vec<_> = (0..distances.len())
+        .filter(|&idx| distances[idx] == min)
+        .collect();
+    if closest.len() == 1 {
+        Some(closest[0])
+    } else {
+        None
+    }
+}
+
+/// Smallest and largest x and y of all coordinates
+fn bounds(coordinates: &[(i32, i32)]) -> (i32, i32, i32, i32) {
+    let xs = coordinates.iter().map(|c| c.0);
+    let ys = coordinates.iter().map(|c| c.1);
+    (
+        xs.clone().min().unwrap(),
+        xs.max().unwrap(),
+        ys.clone().min().unwrap(),
+        ys.max().unwrap(),
+    )
+}
+
+/// Size of the largest finite area
+///
+/// The areas are computed on a grid, which extends far beyond the coordinates. Areas reaching the
+/// border of that grid are infinite. Returns `None` if all areas are infinite.
+pub fn part1(coordinates: &[(i32, i32)]) -> Option<usize> {
+    let (min_x, max_x, min_y, max_y) = bounds(coordinates);
+    let margin = (max_x - min_x) + (max_y - min_y) + 1;
+    let mut sizes = vec![0; coordinates.len()];
+    let mut infinite = vec![false; coordinates.len()];
+    for x in min_x - margin..=max_x + margin {
+        for y in min_y - margin..=max_y + margin {
+            if let Some(idx) = closest(coordinates, (x, y)) {
+                sizes[idx] += 1;
+                let on_border = x == min_x - margin
+                    || x == max_x + margin
+                    || y == min_y - margin
+                    || y == max_y + margin;
+                infinite[idx] |= on_border;
+            }
+        }
+    }
+    (0..coordinates.len())
+        .filter(|&idx| !infinite[idx])
+        .map(|idx| sizes[idx])
+        .max()
+}
+
+/// Number of points whose total distance to all coordinates is less than `limit`
+///
+/// Points further than `limit` from any coordinate cannot count.
+pub fn part2(coordinates: &[(i32, i32)], limit: usize) -> usize {
+    let (min_x, max_x, min_y, max_y) = bounds(coordinates);
+    let limit = limit as i32;
+    let mut count = 0;
+    for x in min_x - limit..=max_x + limit {
+        for y in min_y - limit..=max_y + limit {
+            let total: i32 = coordinates.iter().map(|&c| manhattan(c, (x, y))).sum();
+            if total < limit {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+fn random_coordinates(rng: &mut Rng) -> Vec<(i32, i32)> {
+    let count = rng.range(1, 8);
+    (0..count)
+        .map(|_| (rng.range(0, 12) as i32, rng.range(0, 12) as i32))
+        .collect()
+}
+
+fn to_coordinates(coordinates: &[(i32, i32)]) -> Vec<Coordinate> {
+    coordinates
+        .iter()
+        .enumerate()
+        .map(|(id, &(x, y))| Coordinate {
+            id,
+            position: Point::new(x, y),
+        })
+        .collect()
+}
+
+#[test]
+fn test_part1() {
+    property::check("day06 part1", 200, random_coordinates, |coordinates| {
+        // The puzzle coordinates are distinct
+        let distinct =
+            (1..coordinates.len()).all(|idx| !coordinates[..idx].contains(&coordinates[idx]));
+        if coordinates.is_empty() || !distinct {
+            return Ok(());
+        }
+        // All areas being infinite is an error
+        let expected = part1(coordinates).ok_or(());
+        let input = to_coordinates(coordinates);
+        property::equal(day06::solve_part1(&input).map_err(|_| ()), expected)?;
+        property::equal(day06::solve_part1_rayon(&input).map_err(|_| ()), expected)
+    });
+}
+
+#[test]
+fn test_part2() {
+    let generate = |rng: &mut Rng| (random_coordinates(rng), rng.range(0, 40) as usize);
+    property::check("day06 part2", 200, generate, |(coordinates, limit)| {
+        if coordinates.is_empty() {
+            return Ok(());
+        }
+        let params = Params {
+            distance_limit: *limit,
+        };
+        property::equal(
+            day06::solve_part2_with(&to_coordinates(coordinates), &params),
+            Ok(part2(coordinates, *limit)),
+        )
+    });
+}
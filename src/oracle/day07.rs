@@ -0,0 +1,148 @@
+//! Day 7: The Sum of Its Parts
+
+use day07::{self, Instruction, Params};
+use property;
+use rng::Rng;
+
+/// Number of steps, like in the puzzle input all steps up to the highest one exist
+fn step_count(instructions: &[Instruction]) -> usize {
+    instructions
+        .iter()
+        .map(|inst| inst.step.max(inst.depends_on) as usize + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+fn dependencies_done(instructions: &[Instruction], step: usize, done: &[bool]) -> bool {
+    instructions
+        .iter()
+        .filter(|inst| inst.step as usize == step)
+        .all(|inst| done[inst.depends_on as usize])
+}
+
+/// Order of the steps, always doing the alphabetically first available step next
+pub fn part1(instructions: &[Instruction]) -> String {
+    let count = step_count(instructions);
+    let mut done = vec![false; count];
+    let mut order = String::new();
+    while order.len() < count {
+        let next = (0..count)
+            .find(|&step| !done[step] && dependencies_done(instructions, step, &done))
+            .expect("The dependencies must not have cycles");
+        done[next] = true;
+        order.push((b'A' + next as u8) as char);
+    }
+    order
+}
+
+/// Time to finish all steps, jumping from one finished step to the next
+pub fn part2(instructions: &[Instruction], workers: usize, base_step_cost: usize) -> usize {
+    let count = step_count(instructions);
+    let mut finished_at: Vec<Option<usize>> = vec![None; count];
+    let mut time = 0;
+    loop {
+        let done: Vec<_> = finished_at
+            .iter()
+            .map(|finish| finish.is_some_and(|finish| finish <= time))
+            .collect();
+        let mut idle = workers
+            - finished_at
+                .iter()
+                .filter(|finish| finish.is_some_and(|finish| finish > time))
+                .count();
+        for (step, finish) in finished_at.iter_mut().enumerate() {
+            if idle > 0 && finish.is_none() && dependencies_done(instructions, step, &done) {
+                *finish = Some(time + base_step_cost + step + 1);
+                idle -= 1;
+            }
+        }
+
+        match finished_at
+            .iter()
+            .filter_map(|&finish| finish)
+            .filter(|&finish| finish > time)
+            .min()
+        {
+            Some(next) => time = next,
+            None => return time,
+        }
+    }
+}
+
+/// A seed for the topological order and pairs of steps, which depend on each other
+fn random_dependencies(rng: &mut Rng) -> (u64, Vec<(u8, u8)>) {
+    let count = rng.range(1, 12);
+    let pairs = (0..count)
+        .map(|_| (rng.range(0, 5) as u8, rng.range(0, 5) as u8))
+        .collect();
+    (rng.next_u64(), pairs)
+}
+
+/// Turn the pairs into instructions, where the later step in a random order depends on the other
+fn to_instructions((seed, pairs): &(u64, Vec<(u8, u8)>)) -> Vec<Instruction> {
+    let mut order: Vec<u8> = (0..6).collect();
+    Rng::new(*seed).shuffle(&mut order);
+    let position = |step: u8| order.iter().position(|&s| s == step).unwrap();
+    pairs
+        .iter()
+        .filter(|(a, b)| a != b)
+        .map(|&(a, b)| {
+            if position(a) < position(b) {
+                Instruction {
+                    step: b,
+                    depends_on: a,
+                }
+            } else {
+                Instruction {
+                    step: a,
+                    depends_on: b,
+                }
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_part1() {
+    property::check("day07 part1", 200, random_dependencies, |dependencies| {
+        let instructions = to_instructions(dependencies);
+        if instructions.is_empty() {
+            return Ok(());
+        }
+        property::equal(day07::solve_part1(&instructions), Ok(part1(&instructions)))
+    });
+}
+
+#[test]
+fn test_part2() {
+    let generate = |rng: &mut Rng| {
+        (
+            random_dependencies(rng),
+            rng.range(1, 4) as u8,
+            rng.range(0, 5) as u8,
+        )
+    };
+    property::check(
+        "day07 part2",
+        200,
+        generate,
+        |(dependencies, workers, base_step_cost)| {
+            let instructions = to_instructions(dependencies);
+            if instructions.is_empty() || *workers == 0 {
+                return Ok(());
+            }
+            let params = Params {
+                workers: *workers,
+                base_step_cost: *base_step_cost,
+            };
+            property::equal(
+                day07::solve_part2_with(&instructions, &params),
+                Ok(part2(
+                    &instructions,
+                    *workers as usize,
+                    *base_step_cost as usize,
+                )),
+            )
+        },
+    );
+}
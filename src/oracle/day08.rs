@@ -0,0 +1,105 @@
+//! Day 8: Memory Maneuver
+
+use day08;
+use property::{self, Shrink};
+use rng::Rng;
+
+/// A license tree, which is independent of the parser
+#[derive(Clone, Debug)]
+pub struct Tree {
+    pub children: Vec<Tree>,
+    pub metadata: Vec<usize>,
+}
+
+impl Tree {
+    /// The numbers of the puzzle input describing the tree
+    pub fn numbers(&self) -> Vec<usize> {
+        let mut numbers = vec![self.children.len(), self.metadata.len()];
+        for child in &self.children {
+            numbers.extend(child.numbers());
+        }
+        numbers.extend(&self.metadata);
+        numbers
+    }
+}
+
+impl Shrink for Tree {
+    /// Replace the tree by one of its children, or shrink the children or metadata
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = self.children.clone();
+        candidates.extend(self.children.shrink().into_iter().map(|children| Tree {
+            children,
+            metadata: self.metadata.clone(),
+        }));
+        candidates.extend(self.metadata.shrink().into_iter().map(|metadata| Tree {
+            children: self.children.clone(),
+            metadata,
+        }));
+        candidates
+    }
+}
+
+/// Sum of all metadata entries
+pub fn part1(tree: &Tree) -> usize {
+    let mut sum = 0;
+    let mut stack = vec![tree];
+    while let Some(node) = stack.pop() {
+        sum += node.metadata.iter().sum::<usize>();
+        stack.extend(&node.children);
+    }
+    sum
+}
+
+/// Value of the root, where metadata entries of nodes with children refer to children
+pub fn part2(tree: &Tree) -> usize {
+    if tree.children.is_empty() {
+        return tree.metadata.iter().sum();
+    }
+    let mut value = 0;
+    for &entry in &tree.metadata {
+        if 1 <= entry && entry <= tree.children.len() {
+            value += part2(&tree.children[entry - 1]);
+        }
+    }
+    value
+}
+
+fn random_tree(rng: &mut Rng, depth: usize) -> Tree {
+    let children = if depth == 0 { 0 } else { rng.range(0, 3) };
+    let metadata = rng.range(1, 3);
+    Tree {
+        children: (0..children).map(|_| random_tree(rng, depth - 1)).collect(),
+        metadata: (0..metadata).map(|_| rng.range(0, 4) as usize).collect(),
+    }
+}
+
+fn to_input(tree: &Tree) -> String {
+    let numbers: Vec<_> = tree.numbers().iter().map(|n| n.to_string()).collect();
+    numbers.join(" ")
+}
+
+#[test]
+fn test_part1() {
+    property::check(
+        "day08 part1",
+        200,
+        |rng| random_tree(rng, 4),
+        |tree| {
+            let root = day08::generator(&to_input(tree)).map_err(|err| err.to_string())?;
+            property::equal(day08::solve_part1(&root), part1(tree))
+        },
+    );
+}
+
+#[test]
+fn test_part2() {
+    property::check(
+        "day08 part2",
+        200,
+        |rng| random_tree(rng, 4),
+        |tree| {
+            let root = day08::generator(&to_input(tree)).map_err(|err| err.to_string())?;
+            property::equal(day08::solve_part2(&root), part2(tree))
+        },
+    );
+}
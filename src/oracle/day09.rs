@@ -0,0 +1,49 @@
+//! Day 9: Marble Mania
+
+use day09;
+use property;
+use rng::Rng;
+
+/// Play from the very first marble, keeping the circle in clockwise order in a `Vec`
+pub fn high_score(players: usize, last_marble: usize) -> usize {
+    let mut circle = vec![0];
+    let mut current = 0;
+    let mut scores = vec![0; players];
+    for marble in 1..=last_marble {
+        let player = (marble - 1) % players;
+        if marble % 23 == 0 {
+            current = (current + circle.len() - 7) % circle.len();
+            scores[player] += marble + circle.remove(current);
+            // The marble clockwise of the removed one becomes the current one
+            if current == circle.len() {
+                current = 0;
+            }
+        } else {
+            // Between the marbles one and two positions clockwise of the current one
+            current = (current + 1) % circle.len() + 1;
+            circle.insert(current, marble);
+        }
+    }
+    scores.into_iter().max().unwrap()
+}
+
+#[test]
+fn test_high_score() {
+    let generate = |rng: &mut Rng| (rng.range(1, 12) as usize, rng.range(2, 600) as usize);
+    property::check(
+        "day09 high score",
+        100,
+        generate,
+        |&(players, last_marble)| {
+            if players == 0 || last_marble < 2 {
+                return Ok(());
+            }
+            let expected = high_score(players, last_marble);
+            property::equal(day09::solve_part1_impl(players, last_marble), expected)?;
+            property::equal(
+                day09::solve_part1_impl_linked_list(players, last_marble),
+                expected,
+            )
+        },
+    );
+}
@@ -0,0 +1,101 @@
+//! Day 10: The Stars Align
+
+use budget::BudgetExceeded;
+use day10::{self, Params, Point};
+use geometry::{self, Vector};
+use property;
+use rng::Rng;
+
+/// The first message within `max_seconds` and the seconds it takes to appear
+///
+/// Positions are computed directly for each second, a message appears once all points are less
+/// than 20 rows apart.
+pub fn part1(points: &[Point], max_seconds: usize) -> Option<(String, usize)> {
+    for seconds in 0..=max_seconds as isize {
+        let positions: Vec<_> = points
+            .iter()
+            .map(|p| {
+                (
+                    p.position.x + p.velocity.dx * seconds,
+                    p.position.y + p.velocity.dy * seconds,
+                )
+            })
+            .collect();
+        let min_y = positions.iter().map(|p| p.1).min()?;
+        let max_y = positions.iter().map(|p| p.1).max()?;
+        if max_y - min_y + 1 >= 20 {
+            continue;
+        }
+        let min_x = positions.iter().map(|p| p.0).min()?;
+        let max_x = positions.iter().map(|p| p.0).max()?;
+        let rows: Vec<String> = (min_y..=max_y)
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(|x| {
+                        if positions.contains(&(x, y)) {
+                            '#'
+                        } else {
+                            ' '
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        return Some((rows.join("\n"), seconds as usize));
+    }
+    None
+}
+
+/// Seconds until the message and points as target position and velocity
+fn random_points(rng: &mut Rng) -> (usize, Vec<(i8, i8, i8, i8)>) {
+    let count = rng.range(1, 12);
+    let points = (0..count)
+        .map(|_| {
+            (
+                rng.range(0, 10) as i8,
+                rng.range(0, 10) as i8,
+                rng.range(-3, 3) as i8,
+                rng.range(-3, 3) as i8,
+            )
+        })
+        .collect();
+    (rng.range(0, 30) as usize, points)
+}
+
+fn to_points(seconds: usize, points: &[(i8, i8, i8, i8)]) -> Vec<Point> {
+    points
+        .iter()
+        .map(|&(x, y, delta_x, delta_y)| {
+            let velocity = Vector::new(delta_x as isize, delta_y as isize);
+            Point {
+                position: geometry::Point::new(
+                    x as isize - velocity.dx * seconds as isize,
+                    y as isize - velocity.dy * seconds as isize,
+                ),
+                velocity,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_part1() {
+    property::check("day10 part1", 200, random_points, |(seconds, points)| {
+        if points.is_empty() {
+            return Ok(());
+        }
+        let points = to_points(*seconds, points);
+        let params = Params {
+            max_steps: 40,
+            timeout_ms: 0,
+        };
+        let expected = match part1(&points, 40) {
+            Some((message, seconds)) => Ok(format!(
+                "\n{}\n\nIt would take {} seconds for the message to appear",
+                message, seconds
+            )),
+            None => Err(BudgetExceeded::Steps(40)),
+        };
+        property::equal(day10::solve_part1_with(&points, &params), expected)
+    });
+}
@@ -0,0 +1,161 @@
+//! Day 11: Chronal Charge
+
+use day11::{self, Params};
+use property;
+use rng::Rng;
+
+/// Power level of the fuel cell at `x`, `y`, whose coordinates start at 1
+pub fn power(serial: isize, x: isize, y: isize) -> isize {
+    let rack_id = x + 10;
+    (rack_id * y + serial) * rack_id / 100 % 10 - 5
+}
+
+/// Top left corner and size of the square with the most power, adding up every cell
+///
+/// Ties go to the smallest size, then the smallest x, then the smallest y.
+pub fn best_square(
+    serial: isize,
+    grid_size: isize,
+    sizes: &[isize],
+) -> Option<(isize, isize, isize)> {
+    let mut best: Option<(isize, (isize, isize, isize))> = None;
+    for &size in sizes {
+        for x in 1..=grid_size - size + 1 {
+            for y in 1..=grid_size - size + 1 {
+                let mut total = 0;
+                for dx in 0..size {
+                    for dy in 0..size {
+                        total += power(serial, x + dx, y + dy);
+                    }
+                }
+                if best.is_none_or(|(best_total, _)| total > best_total) {
+                    best = Some((total, (x, y, size)));
+                }
+            }
+        }
+    }
+    best.map(|(_, square)| square)
+}
+
+/// Like `best_square` for all sizes, but using a summed-area table to be fast enough for large grids
+pub fn best_square_summed(serial: isize, grid_size: isize) -> (isize, isize, isize) {
+    let n = grid_size as usize;
+    // sums[y][x] is the power of all cells left of and above x, y
+    let mut sums = vec![vec![0; n + 1]; n + 1];
+    for y in 1..=n {
+        for x in 1..=n {
+            sums[y][x] = power(serial, x as isize, y as isize) + sums[y - 1][x] + sums[y][x - 1]
+                - sums[y - 1][x - 1];
+        }
+    }
+    let mut best = (isize::MIN, (0, 0, 0));
+    for size in 1..=n {
+        for x in 1..=n - size + 1 {
+            for y in 1..=n - size + 1 {
+                let (x2, y2) = (x + size - 1, y + size - 1);
+                let total = sums[y2][x2] - sums[y - 1][x2] - sums[y2][x - 1] + sums[y - 1][x - 1];
+                if total > best.0 {
+                    best = (total, (x as isize, y as isize, size as isize));
+                }
+            }
+        }
+    }
+    best.1
+}
+
+#[test]
+fn test_part1() {
+    let generate = |rng: &mut Rng| {
+        let grid_size = rng.range(1, 12) as usize;
+        (
+            rng.range(0, 9999) as isize,
+            grid_size,
+            rng.range(1, grid_size as i64) as usize,
+        )
+    };
+    property::check(
+        "day11 part1",
+        100,
+        generate,
+        |&(serial, grid_size, area_size)| {
+            if area_size == 0 || area_size > grid_size {
+                return Ok(());
+            }
+            let params = Params {
+                grid_size,
+                area_size,
+                max_area_size: grid_size,
+            };
+            let (x, y, _) = best_square(serial, grid_size as isize, &[area_size as isize]).unwrap();
+            property::equal(
+                day11::solve_part1_with(&serial, &params),
+                format!("{},{}", x, y),
+            )
+        },
+    );
+}
+
+#[test]
+fn test_part2() {
+    let generate = |rng: &mut Rng| (rng.range(0, 9999) as isize, rng.range(1, 12) as usize);
+    property::check("day11 part2", 100, generate, |&(serial, grid_size)| {
+        if grid_size == 0 {
+            return Ok(());
+        }
+        let params = Params {
+            grid_size,
+            area_size: 1,
+            max_area_size: grid_size,
+        };
+        let sizes: Vec<_> = (1..=grid_size as isize).collect();
+        let (x, y, size) = best_square(serial, grid_size as isize, &sizes).unwrap();
+        property::equal(
+            day11::solve_part2_with(&serial, &params),
+            format!("{},{},{}", x, y, size),
+        )?;
+        property::equal(best_square_summed(serial, grid_size as isize), (x, y, size))
+    });
+}
+
+#[test]
+fn test_max_area_size() {
+    // The default search stops at squares of size 19, larger ones never win on these small grids,
+    // which still fit larger squares. On the puzzle's grid the cutoff can fail, see below.
+    let generate = |rng: &mut Rng| (rng.range(1, 9999) as isize, rng.range(20, 40) as usize);
+    property::check(
+        "day11 max area size",
+        100,
+        generate,
+        |&(serial, grid_size)| {
+            if grid_size < 20 {
+                return Ok(());
+            }
+            let params = Params {
+                grid_size,
+                ..Params::default()
+            };
+            let (x, y, size) = best_square_summed(serial, grid_size as isize);
+            property::equal(
+                day11::solve_part2_with(&serial, &params),
+                format!("{},{},{}", x, y, size),
+            )
+        },
+    );
+}
+
+#[test]
+#[ignore]
+fn test_max_area_size_puzzle_grid() {
+    // The cutoff is only a heuristic on the puzzle's grid, e.g., it misses larger squares for the
+    // serials 3 and 88. It holds for the examples and the stored input.
+    // Too slow without optimizations, run it with `cargo test --release -- --ignored`.
+    for &serial in &[18, 42, 7803] {
+        let (x, y, size) = best_square_summed(serial, 300);
+        assert_eq!(
+            day11::solve_part2(&serial),
+            format!("{},{},{}", x, y, size),
+            "serial {}",
+            serial
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Day 12: Subterranean Sustainability
+
+use day12::{self, Params, Rule};
+use property;
+use rng::Rng;
+use std::collections::BTreeSet;
+
+/// Sum of the numbers of all pots with plants after `generations` generations
+///
+/// Plants are kept as a set of pot numbers, each generation checks every pot which could get one.
+pub fn sum_after(plants: &[bool], rules: &[Rule], generations: usize) -> i64 {
+    let mut pots: BTreeSet<i64> = (0..plants.len() as i64)
+        .filter(|&pot| plants[pot as usize])
+        .collect();
+    for _ in 0..generations {
+        let (first, last) = match (pots.iter().next(), pots.iter().next_back()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => break,
+        };
+        pots = (first - 2..=last + 2)
+            .filter(|&pot| {
+                let pattern: Vec<bool> = (pot - 2..=pot + 2).map(|p| pots.contains(&p)).collect();
+                rules
+                    .iter()
+                    .any(|rule| rule.pattern == pattern && rule.new_state)
+            })
+            .collect();
+    }
+    pots.iter().sum()
+}
+
+/// The rules of the patterns producing a plant, which are given by the bits of `mask`
+///
+/// Like in the puzzle input, patterns producing an empty pot have no rule.
+/// The empty pattern never produces a plant, otherwise infinitely many plants would grow.
+fn to_rules(mask: u32) -> Vec<Rule> {
+    (1..32)
+        .filter(|pattern| mask >> pattern & 1 == 1)
+        .map(|pattern: u32| Rule {
+            pattern: (0..5).map(|bit| pattern >> (4 - bit) & 1 == 1).collect(),
+            new_state: true,
+        })
+        .collect()
+}
+
+fn random_pots(rng: &mut Rng) -> (u32, Vec<bool>, usize) {
+    let len = rng.range(0, 15);
+    let plants = (0..len).map(|_| rng.chance(1, 2)).collect();
+    (rng.next_u64() as u32, plants, rng.range(0, 40) as usize)
+}
+
+#[test]
+fn test_part1() {
+    property::check(
+        "day12 part1",
+        200,
+        random_pots,
+        |(mask, plants, generations)| {
+            let rules = to_rules(*mask);
+            let params = Params {
+                generations: *generations,
+                ..Params::default()
+            };
+            property::equal(
+                day12::solve_part1_with(&(plants.clone(), rules.clone()), &params) as i64,
+                sum_after(plants, &rules, *generations),
+            )
+        },
+    );
+}
+
+#[test]
+fn test_part2() {
+    // Few enough generations for the oracle, but enough to skip over cycles
+    property::check(
+        "day12 part2",
+        100,
+        random_pots,
+        |(mask, plants, generations)| {
+            let rules = to_rules(*mask);
+            let long_generations = 100 + *generations * 5;
+            let params = Params {
+                long_generations,
+                max_steps: 0,
+                ..Params::default()
+            };
+            property::equal(
+                day12::solve_part2_with(&(plants.clone(), rules.clone()), &params),
+                Ok(sum_after(plants, &rules, long_generations)),
+            )
+        },
+    );
+}
@@ -0,0 +1,119 @@
+//! Day 13: Mine Cart Madness
+
+use budget::BudgetExceeded;
+use day13::{self, Coordinate, CrashError, Params};
+use property;
+use random_input;
+use rng::Rng;
+
+type Position = (i64, i64);
+
+/// A cart as position, direction as step in x and y, and number of crossings passed
+type Cart = (Position, (i64, i64), usize);
+
+/// First crash and positions of the carts left, working directly on the characters of the input
+///
+/// The first crash is `None` if it does not happen within `max_ticks` ticks. The simulation stops
+/// early once less than two carts are left.
+pub fn simulate(input: &str, max_ticks: usize) -> (Option<Position>, Vec<Position>) {
+    let mut tracks: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+    let mut carts: Vec<Cart> = Vec::new();
+    for (y, row) in tracks.iter_mut().enumerate() {
+        for (x, c) in row.iter_mut().enumerate() {
+            let (direction, track) = match *c {
+                '^' => ((0, -1), '|'),
+                'v' => ((0, 1), '|'),
+                '<' => ((-1, 0), '-'),
+                '>' => ((1, 0), '-'),
+                _ => continue,
+            };
+            carts.push(((x as i64, y as i64), direction, 0));
+            *c = track;
+        }
+    }
+
+    let mut first_crash = None;
+    for _ in 0..max_ticks {
+        if carts.len() < 2 {
+            break;
+        }
+        // Carts move in reading order, crashed ones are removed at the end of the tick
+        carts.sort_by_key(|&((x, y), _, _)| (y, x));
+        let mut crashed = vec![false; carts.len()];
+        for idx in 0..carts.len() {
+            if crashed[idx] {
+                continue;
+            }
+            let ((x, y), (dx, dy), crossings) = carts[idx];
+            let position = (x + dx, y + dy);
+            let direction = match tracks[position.1 as usize][position.0 as usize] {
+                '/' => (-dy, -dx),
+                '\\' => (dy, dx),
+                '+' => match crossings % 3 {
+                    0 => (dy, -dx),
+                    1 => (dx, dy),
+                    _ => (-dy, dx),
+                },
+                _ => (dx, dy),
+            };
+            let crossings =
+                crossings + (tracks[position.1 as usize][position.0 as usize] == '+') as usize;
+            carts[idx] = (position, direction, crossings);
+
+            if let Some(other) = (0..carts.len())
+                .find(|&other| other != idx && !crashed[other] && carts[other].0 == position)
+            {
+                crashed[idx] = true;
+                crashed[other] = true;
+                first_crash = first_crash.or(Some(position));
+            }
+        }
+        carts = carts
+            .into_iter()
+            .zip(crashed)
+            .filter(|(_, crashed)| !crashed)
+            .map(|(cart, _)| cart)
+            .collect();
+    }
+    let left = carts.into_iter().map(|(position, _, _)| position).collect();
+    (first_crash, left)
+}
+
+fn to_coordinate((x, y): Position) -> Coordinate {
+    Coordinate::new(x as isize, y as isize)
+}
+
+/// Seed and size of an input from `random_input`
+fn random_tracks(rng: &mut Rng) -> (u64, usize) {
+    (rng.next_u64(), rng.range(4, 24) as usize)
+}
+
+#[test]
+fn test_simulate() {
+    const MAX_TICKS: usize = 500;
+    let params = Params {
+        max_steps: MAX_TICKS as u64,
+        timeout_ms: 0,
+    };
+    property::check("day13", 100, random_tracks, |&(seed, size)| {
+        if size < 4 {
+            return Ok(());
+        }
+        let input = random_input::generate(13, seed, size).unwrap();
+        let board = day13::generator(&input).map_err(|err| err.to_string())?;
+        let (first_crash, left) = simulate(&input, MAX_TICKS);
+        let budget = CrashError::Budget(BudgetExceeded::Steps(MAX_TICKS as u64));
+        let first_crash = match first_crash {
+            Some(position) => Ok(to_coordinate(position)),
+            None if left.len() < 2 => Err(CrashError::NoCrash),
+            None => Err(budget),
+        };
+        property::equal(day13::solve_part1_with(&board, &params), first_crash)?;
+        let last_cart = match left.len() {
+            0 => Err(CrashError::NoCartLeft),
+            1 => Ok(to_coordinate(left[0])),
+            _ => Err(budget),
+        };
+        property::equal(day13::solve_part2_with(&board, &params), last_cart)
+    });
+}
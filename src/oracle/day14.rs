@@ -0,0 +1,59 @@
+//! Day 14: Chocolate Charts
+
+use day14;
+use property;
+use rng::Rng;
+
+/// The scoreboard with at least `len` receipies
+pub fn scoreboard(len: usize) -> Vec<u8> {
+    let mut scores = vec![3, 7];
+    let (mut first, mut second) = (0, 1);
+    while scores.len() < len {
+        let sum = scores[first] + scores[second];
+        for digit in sum.to_string().chars() {
+            scores.push(digit.to_digit(10).unwrap() as u8);
+        }
+        first = (first + 1 + scores[first] as usize) % scores.len();
+        second = (second + 1 + scores[second] as usize) % scores.len();
+    }
+    scores
+}
+
+/// Scores of the ten receipies after the first `count` ones
+pub fn part1(count: usize) -> String {
+    scoreboard(count + 10)[count..count + 10]
+        .iter()
+        .map(|score| score.to_string())
+        .collect()
+}
+
+/// Number of receipies before `needle` first appears, if it does so within the first `len` ones
+pub fn part2(needle: &[u8], len: usize) -> Option<usize> {
+    scoreboard(len)
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[test]
+fn test_part1() {
+    property::check(
+        "day14 part1",
+        100,
+        |rng| rng.range(0, 3000) as usize,
+        |&count| property::equal(day14::solve_part1(&count), Ok(part1(count))),
+    );
+}
+
+#[test]
+fn test_part2() {
+    // Take the needle from the scoreboard, such that it appears for sure
+    let generate = |rng: &mut Rng| (rng.range(0, 3000) as usize, rng.range(1, 6) as usize);
+    property::check("day14 part2", 200, generate, |&(start, len)| {
+        if len == 0 {
+            return Ok(());
+        }
+        let needle = scoreboard(start + len)[start..start + len].to_vec();
+        let expected = part2(&needle, start + len).unwrap();
+        property::equal(day14::solve_part2(&needle), Ok(expected))
+    });
+}
@@ -0,0 +1,34 @@
+//! Brute force reference implementations of the puzzles
+//!
+//! The oracles follow the puzzle descriptions as literally as possible and ignore performance.
+//! The tests in each module compare the optimized solvers with them on random inputs, using
+//! `property::check`.
+
+#[cfg(feature = "day01")]
+mod day01;
+#[cfg(feature = "day02")]
+mod day02;
+#[cfg(feature = "day03")]
+mod day03;
+#[cfg(feature = "day04")]
+mod day04;
+#[cfg(feature = "day05")]
+mod day05;
+#[cfg(feature = "day06")]
+mod day06;
+#[cfg(feature = "day07")]
+mod day07;
+#[cfg(feature = "day08")]
+mod day08;
+#[cfg(feature = "day09")]
+mod day09;
+#[cfg(feature = "day10")]
+mod day10;
+#[cfg(feature = "day11")]
+mod day11;
+#[cfg(feature = "day12")]
+mod day12;
+#[cfg(feature = "day13")]
+mod day13;
+#[cfg(feature = "day14")]
+mod day14;
@@ -0,0 +1,399 @@
+//! Puzzle parameters which can be changed without recompiling
+//!
+//! Each day with tunable constants exposes a `Params` struct defined with the `params!` macro.
+//! A `Config` holds overrides like `day9.players = 10`, which come from a config file or the
+//! command line, and turns them into the parameters of a day.
+
+use std::{error::Error, fmt, str::FromStr};
+
+/// Parameters of a single day
+pub trait Params: Default {
+    /// Names of all parameters
+    const NAMES: &'static [&'static str];
+
+    /// Set the parameter `name` from its textual representation
+    fn set(&mut self, name: &str, value: &str) -> Result<(), SetError>;
+
+    /// Textual representation of the parameter `name`
+    fn get(&self, name: &str) -> Option<String>;
+
+    /// Check values which parse but which the solver cannot handle
+    fn validate(&self) -> Result<(), Invalid> {
+        Ok(())
+    }
+}
+
+/// A parameter value rejected by `Params::validate`
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Invalid {
+    pub name: &'static str,
+    pub reason: String,
+}
+
+impl Invalid {
+    pub fn new(name: &'static str, reason: &str) -> Self {
+        Invalid {
+            name,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Error while setting a single parameter
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SetError {
+    UnknownName,
+    InvalidValue(String),
+}
+
+/// Parse the value of a parameter
+pub fn parse_value<T>(value: &str) -> Result<T, SetError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|err: T::Err| SetError::InvalidValue(err.to_string()))
+}
+
+/// Define a parameter struct with default values and implement `Params` for it
+///
+/// An optional `validate` block implements `Params::validate`.
+///
+/// ```ignore
+/// params! {
+///     /// Parameters of the marble game
+///     pub struct Params {
+///         /// Number of players
+///         players: usize = 476,
+///     }
+///
+///     validate(params) {
+///         if params.players == 0 {
+///             return Err(::params::Invalid::new("players", "there must be at least one player"));
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+// Builds with only days without parameters do not use it
+#[allow(unused_macros)]
+macro_rules! params {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$field_attr:meta])*
+                $field:ident : $ty:ty = $default:expr,
+            )*
+        }
+    ) => {
+        params! {
+            $(#[$attr])*
+            pub struct $name {
+                $(
+                    $(#[$field_attr])*
+                    $field: $ty = $default,
+                )*
+            }
+
+            validate(_params) {
+                Ok(())
+            }
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$field_attr:meta])*
+                $field:ident : $ty:ty = $default:expr,
+            )*
+        }
+
+        validate($params:ident) $validate:block
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Eq, PartialEq, Hash, Debug)]
+        pub struct $name {
+            $(
+                $(#[$field_attr])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name {
+                    $($field: $default,)*
+                }
+            }
+        }
+
+        impl ::params::Params for $name {
+            const NAMES: &'static [&'static str] = &[$(stringify!($field)),*];
+
+            fn set(&mut self, name: &str, value: &str) -> Result<(), ::params::SetError> {
+                $(
+                    if name == stringify!($field) {
+                        self.$field = ::params::parse_value(value)?;
+                        return Ok(());
+                    }
+                )*
+                Err(::params::SetError::UnknownName)
+            }
+
+            fn get(&self, name: &str) -> Option<String> {
+                $(
+                    if name == stringify!($field) {
+                        return Some(self.$field.to_string());
+                    }
+                )*
+                None
+            }
+
+            fn validate(&self) -> Result<(), ::params::Invalid> {
+                let $params = self;
+                $validate
+            }
+        }
+    };
+}
+
+/// Error in a config file or a `--set` argument
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ConfigError {
+    /// A line which is not of the form `day<N>.<name> = <value>`
+    Syntax { line: usize, text: String },
+    UnknownParameter {
+        day: u8,
+        name: String,
+        expected: &'static [&'static str],
+    },
+    InvalidValue {
+        day: u8,
+        name: String,
+        value: String,
+        reason: String,
+    },
+    /// The day does not have any parameters, or it is not enabled
+    NoParameters(u8),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ConfigError::Syntax { line, text } => write!(
+                f,
+                "line {}: expected `day<N>.<name> = <value>`, found {:?}",
+                line, text
+            ),
+            ConfigError::UnknownParameter {
+                day,
+                name,
+                expected,
+            } => write!(
+                f,
+                "day {} has no parameter `{}`, expected one of: {}",
+                day,
+                name,
+                expected.join(", ")
+            ),
+            ConfigError::InvalidValue {
+                day,
+                name,
+                value,
+                reason,
+            } => write!(
+                f,
+                "day {}: invalid value {:?} for `{}`: {}",
+                day, value, name, reason
+            ),
+            ConfigError::NoParameters(day) => write!(f, "day {} has no parameters", day),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// Overrides for the default parameters of all days
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct Config {
+    /// Day, parameter name, and value in the order they were set
+    values: Vec<(u8, String, String)>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Config::default()
+    }
+
+    /// Parse a config file
+    ///
+    /// Each line has the form `day<N>.<name> = <value>`.
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let mut config = Config::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            config
+                .set_assignment(line)
+                .map_err(|()| ConfigError::Syntax {
+                    line: idx + 1,
+                    text: line.to_string(),
+                })?;
+        }
+        Ok(config)
+    }
+
+    /// Add a single override of the form `day<N>.<name>=<value>`, as given on the command line
+    pub fn parse_assignment(&mut self, assignment: &str) -> Result<(), ConfigError> {
+        self.set_assignment(assignment)
+            .map_err(|()| ConfigError::Syntax {
+                line: 1,
+                text: assignment.to_string(),
+            })
+    }
+
+    fn set_assignment(&mut self, assignment: &str) -> Result<(), ()> {
+        let mut parts = assignment.splitn(2, '=');
+        let key = parts.next().ok_or(())?.trim();
+        let value = parts.next().ok_or(())?.trim();
+        let mut key_parts = key.splitn(2, '.');
+        let day = key_parts.next().ok_or(())?;
+        let name = key_parts.next().ok_or(())?.trim();
+        if !day.starts_with("day") || name.is_empty() {
+            return Err(());
+        }
+        let day = day[3..].parse().map_err(|_| ())?;
+        self.set(day, name, value);
+        Ok(())
+    }
+
+    /// Override a parameter, later values take precedence
+    pub fn set(&mut self, day: u8, name: &str, value: &str) {
+        self.values.push((day, name.to_string(), value.to_string()));
+    }
+
+    /// Merge the overrides of `other`, which take precedence over the existing ones
+    pub fn extend(&mut self, other: Config) {
+        self.values.extend(other.values);
+    }
+
+    /// All days which have overrides, sorted and without duplicates
+    pub fn days(&self) -> Vec<u8> {
+        let mut days: Vec<_> = self.values.iter().map(|(day, _, _)| *day).collect();
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    /// The parameters of `day`, which are the defaults with all overrides applied
+    pub fn params<P: Params>(&self, day: u8) -> Result<P, ConfigError> {
+        let mut params = P::default();
+        for (_, name, value) in self.values.iter().filter(|(d, _, _)| *d == day) {
+            params.set(name, value).map_err(|err| match err {
+                SetError::UnknownName => ConfigError::UnknownParameter {
+                    day,
+                    name: name.clone(),
+                    expected: P::NAMES,
+                },
+                SetError::InvalidValue(reason) => ConfigError::InvalidValue {
+                    day,
+                    name: name.clone(),
+                    value: value.clone(),
+                    reason,
+                },
+            })?;
+        }
+        params.validate().map_err(|invalid| {
+            // A default can be rejected together with an overridden value it depends on
+            let value = self
+                .values
+                .iter()
+                .rev()
+                .find(|(d, name, _)| *d == day && name == invalid.name)
+                .map_or_else(
+                    || params.get(invalid.name).unwrap_or_default(),
+                    |(_, _, value)| value.clone(),
+                );
+            ConfigError::InvalidValue {
+                day,
+                name: invalid.name.to_string(),
+                value,
+                reason: invalid.reason,
+            }
+        })?;
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+params! {
+    /// Parameters for the tests
+    pub struct TestParams {
+        /// Some number
+        count: u8 = 10,
+        width: usize = 300,
+    }
+
+    validate(params) {
+        if params.width == 0 {
+            return Err(::params::Invalid::new("width", "must be positive"));
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_params() {
+    let mut config = Config::parse(
+        "# comment
+day7.count = 5
+
+day07.width=20
+day8.count = 1",
+    )
+    .unwrap();
+    config.parse_assignment("day7.count=6").unwrap();
+    assert_eq!(config.days(), vec![7, 8]);
+    assert_eq!(
+        config.params::<TestParams>(7).unwrap(),
+        TestParams {
+            count: 6,
+            width: 20
+        }
+    );
+    assert_eq!(
+        config.params::<TestParams>(9).unwrap(),
+        TestParams::default()
+    );
+
+    config.set(8, "height", "5");
+    assert_eq!(
+        config.params::<TestParams>(8).unwrap_err().to_string(),
+        "day 8 has no parameter `height`, expected one of: count, width"
+    );
+    config.set(9, "count", "300");
+    assert_eq!(
+        config.params::<TestParams>(9).unwrap_err().to_string(),
+        "day 9: invalid value \"300\" for `count`: number too large to fit in target type"
+    );
+    config.set(10, "width", "0");
+    assert_eq!(
+        config.params::<TestParams>(10).unwrap_err().to_string(),
+        "day 10: invalid value \"0\" for `width`: must be positive"
+    );
+    assert_eq!(
+        Config::parse("day7.count 5").unwrap_err(),
+        ConfigError::Syntax {
+            line: 1,
+            text: "day7.count 5".to_string()
+        }
+    );
+}
@@ -0,0 +1,207 @@
+//! Helpers to parse a line of the puzzle input from left to right
+//!
+//! A `Cursor` consumes the line piece by piece. Errors point to the column at which parsing
+//! failed, so the parsers never need to know the width of a field.
+
+use error::{Line, ParseError};
+use std::str::FromStr;
+
+/// Position within a single line of the input
+#[derive(Copy, Clone, Debug)]
+pub struct Cursor<'a> {
+    line: Line<'a>,
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(line: Line<'a>) -> Self {
+        Cursor {
+            line,
+            rest: line.text(),
+        }
+    }
+
+    pub fn line(&self) -> &Line<'a> {
+        &self.line
+    }
+
+    /// The part of the line, which is not parsed yet
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_end(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    /// Error pointing to the current position
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        self.line.error_at(&self.rest[..0], expected)
+    }
+
+    /// Removes the longest prefix whose characters all match `predicate` and returns it
+    pub fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let end = self
+            .rest
+            .char_indices()
+            .find(|&(_, c)| !predicate(c))
+            .map_or(self.rest.len(), |(idx, _)| idx);
+        let (token, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        token
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    /// Removes exactly `literal`
+    pub fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        self.rest = self.line.strip_prefix(self.rest, literal)?;
+        Ok(())
+    }
+
+    /// Removes a single character, for which `predicate` is true
+    pub fn char_if(
+        &mut self,
+        expected: &str,
+        predicate: impl Fn(char) -> bool,
+    ) -> Result<char, ParseError> {
+        match self.rest.chars().next() {
+            Some(c) if predicate(c) => {
+                self.rest = &self.rest[c.len_utf8()..];
+                Ok(c)
+            }
+            Some(c) => Err(self.line.error_at(&self.rest[..c.len_utf8()], expected)),
+            None => Err(self.error(expected)),
+        }
+    }
+
+    /// Removes an integer with an optional sign and any number of digits
+    pub fn integer<T: FromStr>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let sign = if self.rest.starts_with('-') || self.rest.starts_with('+') {
+            1
+        } else {
+            0
+        };
+        let digits = self.rest[sign..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            return Err(self.line.error_at(&self.rest[sign..sign], expected));
+        }
+        let (token, rest) = self.rest.split_at(sign + digits);
+        // Fails for numbers out of range or a sign on an unsigned type
+        let value = self.line.parse(token, expected)?;
+        self.rest = rest;
+        Ok(value)
+    }
+
+    /// One or more items separated by `separator`
+    ///
+    /// Whitespace around the separator is skipped. A separator consisting only of whitespace
+    /// matches any amount of whitespace.
+    pub fn separated<T, F>(&mut self, separator: &str, mut item: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseError>,
+    {
+        let separator = separator.trim();
+        let mut items = vec![item(self)?];
+        loop {
+            let mut next = *self;
+            let whitespace = next.take_while(char::is_whitespace);
+            let found = if separator.is_empty() {
+                !whitespace.is_empty() && !next.is_end()
+            } else {
+                next.rest.starts_with(separator)
+            };
+            if !found {
+                return Ok(items);
+            }
+            next.rest = &next.rest[separator.len()..];
+            next.skip_whitespace();
+            *self = next;
+            items.push(item(self)?);
+        }
+    }
+
+    /// Error unless the whole line was parsed
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            Err(self.line.error_at(self.rest, "end of line"))
+        }
+    }
+}
+
+#[test]
+fn test_literal() {
+    let mut cursor = Cursor::new(Line::new(1, 1, "Step A"));
+    cursor.literal("Step ").unwrap();
+    assert_eq!(cursor.rest(), "A");
+    assert_eq!(
+        cursor.literal("B!").unwrap_err(),
+        ParseError::new(1, 1, 6, "\"B!\"", "\"A\"")
+    );
+}
+
+#[test]
+fn test_integer() {
+    let mut cursor = Cursor::new(Line::new(1, 1, "-12345678901,+7,  3x"));
+    assert_eq!(cursor.integer::<i64>("a number"), Ok(-12_345_678_901));
+    cursor.literal(",").unwrap();
+    assert_eq!(cursor.integer::<i32>("a number"), Ok(7));
+    cursor.literal(",").unwrap();
+    cursor.skip_whitespace();
+    assert_eq!(cursor.integer::<u8>("a number"), Ok(3));
+    assert_eq!(
+        cursor.integer::<u8>("a number").unwrap_err(),
+        ParseError::new(1, 1, 20, "a number", "\"x\"")
+    );
+}
+
+#[test]
+fn test_integer_out_of_range() {
+    let mut cursor = Cursor::new(Line::new(1, 1, "256"));
+    assert_eq!(
+        cursor.integer::<u8>("a number").unwrap_err(),
+        ParseError::new(1, 1, 1, "a number", "\"256\"")
+    );
+}
+
+#[test]
+fn test_separated() {
+    let mut cursor = Cursor::new(Line::new(1, 1, "1, 2 ,3;"));
+    let numbers = cursor.separated(",", |cursor| cursor.integer::<u8>("a number"));
+    assert_eq!(numbers, Ok(vec![1, 2, 3]));
+    assert_eq!(
+        cursor.end().unwrap_err(),
+        ParseError::new(1, 1, 8, "end of line", "\";\"")
+    );
+
+    let mut cursor = Cursor::new(Line::new(1, 1, "1  2 3 "));
+    let numbers = cursor.separated(" ", |cursor| cursor.integer::<u8>("a number"));
+    assert_eq!(numbers, Ok(vec![1, 2, 3]));
+    assert_eq!(cursor.rest(), " ");
+}
+
+#[test]
+fn test_char_if() {
+    let mut cursor = Cursor::new(Line::new(1, 1, "äb"));
+    assert_eq!(cursor.char_if("a letter", char::is_alphabetic), Ok('ä'));
+    assert_eq!(
+        cursor
+            .char_if("a digit", |c| c.is_ascii_digit())
+            .unwrap_err(),
+        ParseError::new(1, 1, 2, "a digit", "\"b\"")
+    );
+    cursor.take_while(|_| true);
+    assert_eq!(
+        cursor
+            .char_if("a digit", |c| c.is_ascii_digit())
+            .unwrap_err(),
+        ParseError::new(1, 1, 3, "a digit", "end of line")
+    );
+}
@@ -0,0 +1,216 @@
+//! Minimal property based testing, used to compare the solvers with the reference implementations
+//!
+//! `check` runs a property on many random values. If it fails, the value is shrunk to a minimal
+//! failing value, which is much easier to debug than the original one.
+//! Panics count as failures, so solvers which crash are found as well.
+
+use rng::Rng;
+use std::{fmt::Debug, panic};
+
+/// Values which can be simplified once a property failed for them
+pub trait Shrink: Clone + Debug {
+    /// Simpler variants of the value, the most promising ones first
+    fn shrink(&self) -> Vec<Self>;
+}
+
+impl Shrink for bool {
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Shrink for char {
+    fn shrink(&self) -> Vec<Self> {
+        if *self == 'a' {
+            Vec::new()
+        } else {
+            vec!['a']
+        }
+    }
+}
+
+macro_rules! impl_shrink_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Shrink for $t {
+                /// Move towards 0
+                #[allow(unused_comparisons)]
+                fn shrink(&self) -> Vec<Self> {
+                    let x = *self;
+                    let mut candidates = vec![0, x / 2];
+                    if x > 0 {
+                        candidates.push(x - 1);
+                    }
+                    if x < 0 {
+                        candidates.push(x + 1);
+                    }
+                    candidates.dedup();
+                    candidates.retain(|&c| c != x);
+                    candidates
+                }
+            }
+        )*
+    };
+}
+
+impl_shrink_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<T: Shrink> Shrink for Vec<T> {
+    /// Remove elements, starting with large chunks, then shrink single elements
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = Vec::new();
+        if self.is_empty() {
+            return candidates;
+        }
+        candidates.push(Vec::new());
+        if self.len() > 2 {
+            let half = self.len() / 2;
+            candidates.push(self[..half].to_vec());
+            candidates.push(self[half..].to_vec());
+        }
+        if self.len() > 1 {
+            for idx in 0..self.len() {
+                let mut smaller = self.clone();
+                smaller.remove(idx);
+                candidates.push(smaller);
+            }
+        }
+        for (idx, item) in self.iter().enumerate() {
+            for simpler in item.shrink() {
+                let mut candidate = self.clone();
+                candidate[idx] = simpler;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+impl<A: Shrink, B: Shrink> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates: Vec<_> = self
+            .0
+            .shrink()
+            .into_iter()
+            .map(|a| (a, self.1.clone()))
+            .collect();
+        candidates.extend(self.1.shrink().into_iter().map(|b| (self.0.clone(), b)));
+        candidates
+    }
+}
+
+impl<A: Shrink, B: Shrink, C: Shrink> Shrink for (A, B, C) {
+    fn shrink(&self) -> Vec<Self> {
+        ((self.0.clone(), self.1.clone()), self.2.clone())
+            .shrink()
+            .into_iter()
+            .map(|((a, b), c)| (a, b, c))
+            .collect()
+    }
+}
+
+impl<A: Shrink, B: Shrink, C: Shrink, D: Shrink> Shrink for (A, B, C, D) {
+    fn shrink(&self) -> Vec<Self> {
+        (
+            (self.0.clone(), self.1.clone()),
+            (self.2.clone(), self.3.clone()),
+        )
+            .shrink()
+            .into_iter()
+            .map(|((a, b), (c, d))| (a, b, c, d))
+            .collect()
+    }
+}
+
+/// Check `property` for the values generated from the seeds `0..cases`
+///
+/// Panics with the minimal failing value, if the property fails for any of them. Properties can
+/// accept values outside of their domain by returning `Ok`, so those are never reported.
+pub fn check<T, G, P>(name: &str, cases: u64, generate: G, property: P)
+where
+    T: Shrink,
+    G: Fn(&mut Rng) -> T,
+    P: Fn(&T) -> Result<(), String>,
+{
+    for seed in 0..cases {
+        let value = generate(&mut Rng::new(seed));
+        if let Err(err) = run(&property, &value) {
+            let (value, err) = shrink(&property, value, err);
+            panic!(
+                "Property `{}` failed for seed {}: {}\nMinimal failing value: {:?}",
+                name, seed, err, value
+            );
+        }
+    }
+}
+
+/// Run the property, treating panics as failures
+fn run<T, P>(property: &P, value: &T) -> Result<(), String>
+where
+    P: Fn(&T) -> Result<(), String>,
+{
+    match panic::catch_unwind(panic::AssertUnwindSafe(|| property(value))) {
+        Ok(res) => res,
+        Err(payload) => Err(payload
+            .downcast_ref::<&str>()
+            .map(|msg| msg.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .map(|msg| format!("panicked: {}", msg))
+            .unwrap_or_else(|| "panicked".to_string())),
+    }
+}
+
+/// Repeatedly replace the value with its first simplification which still fails
+fn shrink<T, P>(property: &P, mut value: T, mut err: String) -> (T, String)
+where
+    T: Shrink,
+    P: Fn(&T) -> Result<(), String>,
+{
+    const MAX_STEPS: usize = 1000;
+    'shrink: for _ in 0..MAX_STEPS {
+        for candidate in value.shrink() {
+            if let Err(candidate_err) = run(property, &candidate) {
+                value = candidate;
+                err = candidate_err;
+                continue 'shrink;
+            }
+        }
+        break;
+    }
+    (value, err)
+}
+
+/// Compare the result of a solver with the one of the reference implementation
+pub fn equal<T: PartialEq + Debug>(actual: T, expected: T) -> Result<(), String> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!("expected {:?}, got {:?}", expected, actual))
+    }
+}
+
+#[test]
+fn test_shrink() {
+    let sum_below_10 = |values: &Vec<u8>| {
+        if values.iter().map(|&v| u32::from(v)).sum::<u32>() < 10 {
+            Ok(())
+        } else {
+            Err("too large".to_string())
+        }
+    };
+    let (value, _) = shrink(&sum_below_10, vec![50, 3, 20], "too large".to_string());
+    assert_eq!(value, vec![10]);
+
+    let no_panic = |&(a, b): &(i32, i32)| {
+        assert!(a < 3 || b > -5, "boom");
+        Ok(())
+    };
+    let (value, err) = shrink(&no_panic, (100, -100), String::new());
+    assert_eq!(value, (3, -5));
+    assert_eq!(err, "panicked: boom");
+    assert_eq!(run(&no_panic, &(0, 0)), Ok(()));
+}
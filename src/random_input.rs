@@ -0,0 +1,704 @@
+//! Seeded generators for random, but valid, puzzle inputs
+//!
+//! Each generator produces the text of an input in the same format as the real puzzle inputs.
+//! The same seed and sizes always produce the same text, so generated inputs can be used for
+//! stress tests and to measure how the solvers scale.
+//!
+//! The inputs satisfy the promises of the puzzle descriptions, e.g., there is a claim without
+//! overlaps on day 3 and the step graph of day 7 is acyclic. Some searches can still run for a
+//! very long time, e.g., carts might never crash on day 13, so solve them with a budget.
+
+use rng::Rng;
+
+/// Generate an input for `day` whose size grows with `size`, using defaults for all other knobs
+///
+/// The `size` is roughly the number of lines or elements of the input, it is ignored for the days
+/// whose input is a single number. Returns `None` for days without a generator.
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let mut rng = Rng::new(seed);
+    let rng = &mut rng;
+    let size = size.max(1);
+    Some(match day {
+        1 => day01(rng, size),
+        2 => day02(rng, size.max(2), 26),
+        3 => day03(rng, size, 1000, 30),
+        4 => day04(rng, size, (size / 15).clamp(1, MAX_GUARDS)),
+        5 => day05(rng, size, 26),
+        6 => day06(rng, size.max(5), (size as i32 * 7).max(8)),
+        7 => day07(rng, size.clamp(2, 26) as u8, size),
+        8 => day08(rng, size, 5, 10),
+        9 => day09(rng, 500, size * 100),
+        10 => day10(rng, (size / 30).max(1), 10_000),
+        11 => day11(rng),
+        12 => day12(rng, size),
+        13 => day13(
+            rng,
+            size.max(4),
+            (size / 6).max(1),
+            (size / 8).max(1) * 2 + 1,
+        ),
+        14 => day14(rng),
+        _ => return None,
+    })
+}
+
+/// Frequency changes between -20 and 20, none of them 0
+pub fn day01(rng: &mut Rng, changes: usize) -> String {
+    let lines: Vec<_> = (0..changes)
+        .map(|_| {
+            let change = loop {
+                let change = rng.range(-20, 20);
+                if change != 0 {
+                    break change;
+                }
+            };
+            format!("{:+}", change)
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Box ids of `length` lowercase letters, of which exactly two differ by a single letter
+///
+/// Panics if there are not enough ids of `length` letters which differ in more than one letter.
+pub fn day02(rng: &mut Rng, ids: usize, length: usize) -> String {
+    assert!(
+        ids >= 2 && length >= 1,
+        "Need at least two ids with letters"
+    );
+    let mut boxes: Vec<Vec<u8>> = Vec::with_capacity(ids);
+    let mut attempts = 0;
+    while boxes.len() < ids - 1 {
+        attempts += 1;
+        assert!(
+            attempts < ids * 1000,
+            "Too many ids for a length of {}",
+            length
+        );
+        let id: Vec<u8> = (0..length).map(|_| random_letter(rng, 26)).collect();
+        if boxes.iter().all(|other| differences(&id, other) > 1) {
+            boxes.push(id);
+        }
+    }
+
+    // Copy one id and change a single letter, such that it is only similar to the original
+    loop {
+        attempts += 1;
+        assert!(
+            attempts < ids * 1000,
+            "Too many ids for a length of {}",
+            length
+        );
+        let original = rng.index(boxes.len());
+        let mut similar = boxes[original].clone();
+        let idx = rng.index(length);
+        similar[idx] = b'a' + ((similar[idx] - b'a') + 1 + rng.below(25) as u8) % 26;
+        let unique = boxes
+            .iter()
+            .enumerate()
+            .all(|(i, other)| i == original || differences(&similar, other) > 1);
+        if unique {
+            let idx = rng.index(boxes.len() + 1);
+            boxes.insert(idx, similar);
+            break;
+        }
+    }
+
+    let lines: Vec<_> = boxes
+        .into_iter()
+        .map(|id| String::from_utf8(id).expect("Letters are valid UTF-8"))
+        .collect();
+    lines.join("\n")
+}
+
+/// Number of positions at which the two ids differ
+fn differences(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).filter(|(a, b)| a != b).count()
+}
+
+/// Lowercase letter out of the first `types` letters of the alphabet
+fn random_letter(rng: &mut Rng, types: u8) -> u8 {
+    b'a' + rng.below(u64::from(types)) as u8
+}
+
+/// Claims of up to `max_claim_size` square inches on a fabric of `fabric_size` square inches
+///
+/// One claim does not overlap with any other. If there is no space left for it, it is placed
+/// right of the fabric.
+pub fn day03(rng: &mut Rng, claims: usize, fabric_size: u32, max_claim_size: u32) -> String {
+    assert!(claims >= 1, "Need at least one claim");
+    assert!(
+        1 <= max_claim_size && max_claim_size <= fabric_size,
+        "Claims must fit on the fabric"
+    );
+    // (x, y, width, height)
+    let random_claim = |rng: &mut Rng| {
+        let width = rng.range(1, i64::from(max_claim_size)) as u32;
+        let height = rng.range(1, i64::from(max_claim_size)) as u32;
+        let x = rng.range(0, i64::from(fabric_size - width)) as u32;
+        let y = rng.range(0, i64::from(fabric_size - height)) as u32;
+        (x, y, width, height)
+    };
+    let overlaps = |a: &(u32, u32, u32, u32), b: &(u32, u32, u32, u32)| {
+        a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+    };
+
+    let mut rects: Vec<_> = (1..claims).map(|_| random_claim(rng)).collect();
+    let free = (0..1000)
+        .map(|_| random_claim(rng))
+        .find(|claim| rects.iter().all(|other| !overlaps(claim, other)))
+        .unwrap_or((fabric_size, 0, 1, 1));
+    let idx = rng.index(rects.len() + 1);
+    rects.insert(idx, free);
+
+    let lines: Vec<_> = rects
+        .iter()
+        .enumerate()
+        .map(|(idx, (x, y, width, height))| {
+            format!("#{} @ {},{}: {}x{}", idx + 1, x, y, width, height)
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Highest guard id used by `day04`
+pub const MAX_GUARDS: usize = 4000;
+
+/// Unordered records of `shifts` shifts on consecutive days, done by `guards` different guards
+///
+/// Each guard takes up to three naps per shift, during the first shift at least one.
+pub fn day04(rng: &mut Rng, shifts: usize, guards: usize) -> String {
+    assert!(shifts >= 1, "Need at least one shift");
+    assert!(
+        (1..=MAX_GUARDS).contains(&guards),
+        "The number of guards must be between 1 and {}",
+        MAX_GUARDS
+    );
+    let mut ids: Vec<_> = (1..=MAX_GUARDS).collect();
+    rng.shuffle(&mut ids);
+    ids.truncate(guards);
+
+    let mut lines = Vec::new();
+    for shift in 0..shifts {
+        let day = shift + 1;
+        let guard = *rng.choose(&ids);
+        // Shifts begin shortly before or after midnight, the guards only sleep after midnight
+        let (begin, first_minute) = if rng.chance(1, 2) {
+            (format!("{} 23:{:02}", date(day - 1), rng.range(45, 59)), 0)
+        } else {
+            let minute = rng.range(0, 10);
+            (format!("{} 00:{:02}", date(day), minute), minute + 1)
+        };
+        lines.push(format!("[{}] Guard #{} begins shift", begin, guard));
+
+        // Alternately fall asleep and wake up at distinct minutes
+        let naps = rng.range(if shift == 0 { 1 } else { 0 }, 3) as usize;
+        let mut minutes: Vec<_> = (first_minute..60).collect();
+        rng.shuffle(&mut minutes);
+        minutes.truncate(2 * naps);
+        minutes.sort();
+        for (idx, minute) in minutes.into_iter().enumerate() {
+            let event = if idx % 2 == 0 {
+                "falls asleep"
+            } else {
+                "wakes up"
+            };
+            lines.push(format!("[{} 00:{:02}] {}", date(day), minute, event));
+        }
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+/// Date `days` days after 1518-01-01, ignoring leap years
+fn date(days: usize) -> String {
+    const MONTH_LENGTHS: [usize; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    let year = 1518 + days / 365;
+    let mut day = days % 365;
+    let mut month = 0;
+    while day >= MONTH_LENGTHS[month] {
+        day -= MONTH_LENGTHS[month];
+        month += 1;
+    }
+    format!("{}-{:02}-{:02}", year, month + 1, day + 1)
+}
+
+/// Polymer of `length` units, using the first `unit_types` letters in both polarities
+pub fn day05(rng: &mut Rng, length: usize, unit_types: u8) -> String {
+    assert!(
+        (1..=26).contains(&unit_types),
+        "There are between 1 and 26 unit types"
+    );
+    (0..length)
+        .map(|_| {
+            let unit = random_letter(rng, unit_types);
+            if rng.chance(1, 2) {
+                unit.to_ascii_uppercase() as char
+            } else {
+                unit as char
+            }
+        })
+        .collect()
+}
+
+/// Distinct coordinates between 0 and `extent` - 1
+///
+/// Five of the coordinates form a plus sign in the center, such that at least the area of the
+/// center is finite.
+pub fn day06(rng: &mut Rng, coordinates: usize, extent: i32) -> String {
+    assert!(extent >= 8, "The extent must be at least 8");
+    assert!(
+        5 <= coordinates && coordinates <= (extent * extent) as usize,
+        "Need between 5 and extent^2 coordinates"
+    );
+    let center = extent / 2;
+    let distance = extent / 4;
+    let mut points = vec![
+        (center, center),
+        (center - distance, center),
+        (center + distance, center),
+        (center, center - distance),
+        (center, center + distance),
+    ];
+    while points.len() < coordinates {
+        let point = (
+            rng.range(0, i64::from(extent) - 1) as i32,
+            rng.range(0, i64::from(extent) - 1) as i32,
+        );
+        if !points.contains(&point) {
+            points.push(point);
+        }
+    }
+    rng.shuffle(&mut points);
+
+    let lines: Vec<_> = points
+        .into_iter()
+        .map(|(x, y)| format!("{}, {}", x, y))
+        .collect();
+    lines.join("\n")
+}
+
+/// Acyclic dependencies between the first `steps` letters
+///
+/// Every step but one depends on another step, so all steps appear in the instructions.
+/// On top of that, up to `extra_dependencies` random dependencies are added.
+pub fn day07(rng: &mut Rng, steps: u8, extra_dependencies: usize) -> String {
+    assert!(
+        (2..=26).contains(&steps),
+        "There are between 2 and 26 steps"
+    );
+    // A random topological order, dependencies always point to earlier steps
+    let mut order: Vec<u8> = (0..steps).collect();
+    rng.shuffle(&mut order);
+
+    let mut dependencies = Vec::new();
+    for idx in 1..order.len() {
+        dependencies.push((order[rng.index(idx)], order[idx]));
+    }
+    for _ in 0..extra_dependencies {
+        let a = rng.index(order.len());
+        let b = rng.index(order.len());
+        let dependency = (order[a.min(b)], order[a.max(b)]);
+        if a != b && !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
+        }
+    }
+    rng.shuffle(&mut dependencies);
+
+    let lines: Vec<_> = dependencies
+        .into_iter()
+        .map(|(before, step)| {
+            format!(
+                "Step {} must be finished before step {} can begin.",
+                (b'A' + before) as char,
+                (b'A' + step) as char
+            )
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// License tree with `nodes` nodes, each having up to `max_children` children and between 1 and
+/// `max_metadata` metadata entries
+///
+/// The metadata of nodes with children mostly refers to those children. The depth of the tree
+/// grows with the logarithm of `nodes`, unless `max_children` is 1.
+pub fn day08(rng: &mut Rng, nodes: usize, max_children: usize, max_metadata: usize) -> String {
+    assert!(nodes >= 1 && max_metadata >= 1, "Nodes need metadata");
+    assert!(
+        nodes == 1 || max_children >= 1,
+        "Multiple nodes require children"
+    );
+    // Attach each node to a random earlier node, which still has space for children
+    let mut children = vec![Vec::new(); nodes];
+    let mut parents = vec![0];
+    for node in 1..nodes {
+        let idx = rng.index(parents.len());
+        let parent = parents[idx];
+        children[parent].push(node);
+        if children[parent].len() == max_children {
+            parents.swap_remove(idx);
+        }
+        parents.push(node);
+    }
+
+    let mut numbers = Vec::new();
+    push_node(rng, &children, 0, max_metadata, &mut numbers);
+    let numbers: Vec<_> = numbers.into_iter().map(|n| n.to_string()).collect();
+    numbers.join(" ")
+}
+
+fn push_node(
+    rng: &mut Rng,
+    children: &[Vec<usize>],
+    node: usize,
+    max_metadata: usize,
+    numbers: &mut Vec<i64>,
+) {
+    let metadata = rng.range(1, max_metadata as i64);
+    numbers.push(children[node].len() as i64);
+    numbers.push(metadata);
+    for &child in &children[node] {
+        push_node(rng, children, child, max_metadata, numbers);
+    }
+    let max_value = if children[node].is_empty() {
+        9
+    } else {
+        children[node].len() as i64 + 1
+    };
+    for _ in 0..metadata {
+        numbers.push(rng.range(1, max_value));
+    }
+}
+
+/// Points of light which form `letters` random glyphs of 6x10 points after `seconds` seconds
+pub fn day10(rng: &mut Rng, letters: usize, seconds: u32) -> String {
+    const WIDTH: i64 = 6;
+    const HEIGHT: i64 = 10;
+    let mut lines = Vec::new();
+    for letter in 0..letters as i64 {
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                if !rng.chance(1, 2) {
+                    continue;
+                }
+                let (delta_x, delta_y) = (rng.range(-5, 5), rng.range(-5, 5));
+                lines.push(format!(
+                    "position=<{:>6}, {:>6}> velocity=<{:>2}, {:>2}>",
+                    letter * (WIDTH + 2) + x - delta_x * i64::from(seconds),
+                    y - delta_y * i64::from(seconds),
+                    delta_x,
+                    delta_y
+                ));
+            }
+        }
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+/// Game of up to `max_players` players, which ends with the marble `last_marble`
+pub fn day09(rng: &mut Rng, max_players: usize, last_marble: usize) -> String {
+    assert!(
+        max_players >= 1 && last_marble >= 1,
+        "The game needs players and marbles"
+    );
+    format!(
+        "{} players; last marble is worth {} points",
+        rng.range(1, max_players as i64),
+        last_marble
+    )
+}
+
+/// Grid serial number between 1 and 9999
+pub fn day11(rng: &mut Rng) -> String {
+    rng.range(1, 9999).to_string()
+}
+
+/// Initial state of `pots` pots and all 32 rules, of which the empty pattern stays empty
+pub fn day12(rng: &mut Rng, pots: usize) -> String {
+    let pot = |plant| if plant { '#' } else { '.' };
+    let state: String = (0..pots).map(|_| pot(rng.chance(1, 2))).collect();
+
+    let mut rules: Vec<_> = (0..32)
+        .map(|pattern| {
+            let text: String = (0..5)
+                .rev()
+                .map(|bit| pot(pattern >> bit & 1 == 1))
+                .collect();
+            let plant = pattern != 0 && rng.chance(1, 2);
+            format!("{} => {}", text, pot(plant))
+        })
+        .collect();
+    rng.shuffle(&mut rules);
+    format!("initial state: {}\n\n{}", state, rules.join("\n"))
+}
+
+/// Track piece of the day 13 generator, corners remember their position within their loop
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Track {
+    Empty,
+    Horizontal,
+    Vertical,
+    Crossing,
+    Corner { top: bool, left: bool },
+}
+
+impl Track {
+    fn to_char(self) -> char {
+        match self {
+            Track::Empty => ' ',
+            Track::Horizontal => '-',
+            Track::Vertical => '|',
+            Track::Crossing => '+',
+            Track::Corner { top, left } if top == left => '/',
+            Track::Corner { .. } => '\\',
+        }
+    }
+
+    fn connects_east(self) -> bool {
+        match self {
+            Track::Horizontal | Track::Crossing => true,
+            Track::Corner { left, .. } => left,
+            Track::Empty | Track::Vertical => false,
+        }
+    }
+}
+
+/// Up to `loops` rectangular loops on a `size` x `size` grid with up to `carts` carts on them
+///
+/// Loops only touch at crossings. There are at least two carts, but they might never crash.
+pub fn day13(rng: &mut Rng, size: usize, loops: usize, carts: usize) -> String {
+    assert!(
+        size >= 4 && loops >= 1,
+        "Need a grid of at least 4x4 and a loop"
+    );
+    assert!(carts >= 2, "Need at least two carts");
+    let mut grid = vec![vec![Track::Empty; size]; size];
+    let mut placed = 0;
+    for _ in 0..loops * 100 {
+        if placed == loops {
+            break;
+        }
+        if place_loop(rng, &mut grid) {
+            placed += 1;
+        }
+    }
+
+    let mut rows: Vec<Vec<char>> = grid
+        .iter()
+        .map(|row| row.iter().map(|track| track.to_char()).collect())
+        .collect();
+    let mut straights: Vec<_> = (0..size)
+        .flat_map(|y| (0..size).map(move |x| (x, y)))
+        .filter(|&(x, y)| grid[y][x] == Track::Horizontal || grid[y][x] == Track::Vertical)
+        .collect();
+    rng.shuffle(&mut straights);
+    for &(x, y) in straights.iter().take(carts) {
+        let directions = if grid[y][x] == Track::Horizontal {
+            ['<', '>']
+        } else {
+            ['^', 'v']
+        };
+        rows[y][x] = *rng.choose(&directions);
+    }
+
+    let lines: Vec<String> = rows
+        .into_iter()
+        .map(|row| row.into_iter().collect())
+        .collect();
+    lines.join("\n")
+}
+
+/// Try to add a random loop of at least 3x3 to the grid, returns whether it fit
+///
+/// New tracks can only cross existing ones. The corners `/` and `\` are context sensitive, so a
+/// loop is also rejected if any corner would parse differently afterwards.
+fn place_loop(rng: &mut Rng, grid: &mut Vec<Vec<Track>>) -> bool {
+    let size = grid.len();
+    let left = rng.index(size - 2);
+    let right = left + 2 + rng.index(size - 2 - left);
+    let top = rng.index(size - 2);
+    let bottom = top + 2 + rng.index(size - 2 - top);
+
+    let mut cells = vec![
+        (
+            left,
+            top,
+            Track::Corner {
+                top: true,
+                left: true,
+            },
+        ),
+        (
+            right,
+            top,
+            Track::Corner {
+                top: true,
+                left: false,
+            },
+        ),
+        (
+            left,
+            bottom,
+            Track::Corner {
+                top: false,
+                left: true,
+            },
+        ),
+        (
+            right,
+            bottom,
+            Track::Corner {
+                top: false,
+                left: false,
+            },
+        ),
+    ];
+    for x in left + 1..right {
+        cells.push((x, top, Track::Horizontal));
+        cells.push((x, bottom, Track::Horizontal));
+    }
+    for y in top + 1..bottom {
+        cells.push((left, y, Track::Vertical));
+        cells.push((right, y, Track::Vertical));
+    }
+
+    let mut new = grid.clone();
+    for (x, y, track) in cells {
+        new[y][x] = match (new[y][x], track) {
+            (Track::Empty, track) => track,
+            (Track::Horizontal, Track::Vertical) | (Track::Vertical, Track::Horizontal) => {
+                Track::Crossing
+            }
+            _ => return false,
+        };
+    }
+
+    // A corner connects west, iff the field left of it connects east
+    // Nothing connects from the west into the first column
+    let corners_parse = new.iter().all(|row| {
+        row.iter().enumerate().all(|(x, track)| match track {
+            Track::Corner { left, .. } => (x > 0 && row[x - 1].connects_east()) != *left,
+            _ => true,
+        })
+    });
+    if corners_parse {
+        *grid = new;
+    }
+    corners_parse
+}
+
+/// Number of receipies between 100000 and 999999
+pub fn day14(rng: &mut Rng) -> String {
+    rng.range(100_000, 999_999).to_string()
+}
+
+#[test]
+fn test_reproducible() {
+    for day in 1..=25 {
+        assert_eq!(generate(day, 17, 50), generate(day, 17, 50), "day {}", day);
+    }
+    assert_ne!(generate(5, 1, 50), generate(5, 2, 50));
+    assert_eq!(generate(15, 1, 50), None);
+    assert_eq!(date(0), "1518-01-01");
+    assert_eq!(date(59), "1518-03-01");
+    assert_eq!(date(365 + 364), "1519-12-31");
+}
+
+#[test]
+fn test_inputs_solve() {
+    use registry;
+
+    // Those solvers always finish on valid inputs, the others might need a budget
+    let finishing = [
+        (2, 1),
+        (2, 2),
+        (3, 1),
+        (3, 2),
+        (4, 1),
+        (4, 2),
+        (5, 1),
+        (5, 2),
+        (6, 1),
+        (6, 2),
+        (7, 1),
+        (7, 2),
+        (8, 1),
+        (8, 2),
+        (9, 1),
+        (9, 2),
+        (10, 1),
+        (12, 1),
+        (14, 1),
+    ];
+    for seed in 0..4 {
+        for &(day, part) in &finishing {
+            let solvers = registry::solvers()
+                .into_iter()
+                .filter(|s| s.day == day && s.part == part);
+            for solver in solvers {
+                let input = generate(day, seed, 30).unwrap();
+                if let Err(err) = solver.run(&input) {
+                    panic!(
+                        "day {} part {} {} failed for seed {}: {}\n{}",
+                        day,
+                        part,
+                        solver.variant_name(),
+                        seed,
+                        err,
+                        input
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "day13")]
+#[test]
+fn test_day13_tracks() {
+    use day13;
+    use simulation::Simulation;
+
+    let mut first_column_used = false;
+    for seed in 0..20 {
+        let input = generate(13, seed, 40).unwrap();
+        first_column_used |= input
+            .lines()
+            .any(|line| line.chars().next().is_some_and(|c| c != ' '));
+        let mut board = day13::generator(&input).unwrap();
+        assert!(board.carts.len() >= 2);
+        // Carts leaving the tracks would panic
+        board.run(1000);
+    }
+    assert!(first_column_used);
+}
+
+#[test]
+fn test_inputs_parse() {
+    use error::ParseError;
+    use params::Config;
+    use registry;
+
+    for day in 1..=25 {
+        for seed in 0..3 {
+            let input = match generate(day, seed, 30) {
+                Some(input) => input,
+                None => continue,
+            };
+            // A budget of one step stops the searches quickly, but only after parsing
+            let mut config = Config::new();
+            if [1, 10, 12, 13, 14].contains(&day) {
+                config.set(day, "max_steps", "1");
+            }
+            for solver in registry::solvers().into_iter().filter(|s| s.day == day) {
+                if let Err(err) = solver.run_with(&input, &config) {
+                    if let Some(err) = err.downcast_ref::<ParseError>() {
+                        panic!("day {} seed {}: {}\n{}", day, seed, err, input);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,639 @@
+//! List of all solvers, such that they can be run without `cargo aoc`
+
+use answer::{Answer, IntoAnswer};
+use hashbrown::HashMap;
+use input;
+use observer::Reporter;
+use params::{Config, ConfigError};
+use rayon::prelude::*;
+use std::{
+    cmp::Reverse,
+    error::Error,
+    io,
+    time::{Duration, Instant},
+};
+
+pub type SolverError = Box<dyn Error + Send + Sync>;
+
+/// A single solver for one part of a day
+#[derive(Copy, Clone)]
+pub struct Solver {
+    pub day: u8,
+    pub part: u8,
+    /// Name of an alternative implementation, `None` for the default one
+    pub variant: Option<&'static str>,
+    runner: fn(&str, &Config, &mut Reporter) -> Result<Run, SolverError>,
+}
+
+/// Answer of a single solver run together with the time spent in each phase
+#[derive(Clone, Debug)]
+pub struct Run {
+    pub answer: Answer,
+    /// Time spent parsing the input, zero for solvers without a generator
+    pub generator_time: Duration,
+    pub solver_time: Duration,
+}
+
+impl Solver {
+    /// Parse the `input` and solve the puzzle
+    ///
+    /// The input is normalized first, see `input::normalize`.
+    pub fn run(&self, input: &str) -> Result<String, SolverError> {
+        self.run_timed(input).map(|run| run.answer.to_string())
+    }
+
+    /// Like `run`, but also measure how long the generator and the solver took
+    pub fn run_timed(&self, input: &str) -> Result<Run, SolverError> {
+        self.run_with(input, &Config::default())
+    }
+
+    /// Like `run_timed`, but with the puzzle parameters taken from `config`
+    pub fn run_with(&self, input: &str, config: &Config) -> Result<Run, SolverError> {
+        self.run_observed(input, config, &mut Reporter::silent())
+    }
+
+    /// Like `run_with`, but long running solvers report their progress to `reporter`
+    ///
+    /// Solvers without progress reports ignore the `reporter`.
+    pub fn run_observed(
+        &self,
+        input: &str,
+        config: &Config,
+        reporter: &mut Reporter,
+    ) -> Result<Run, SolverError> {
+        (self.runner)(&input::normalize(input), config, reporter)
+    }
+
+    /// Name of the variant as shown to the user
+    pub fn variant_name(&self) -> &'static str {
+        self.variant.unwrap_or("default")
+    }
+}
+
+/// Register a solver, optionally with a generator which parses the input first
+///
+/// Solvers marked `with params` receive the `Params` of their module as second argument.
+/// Solvers marked `with params and observer` additionally receive a `Reporter` as third argument.
+macro_rules! solver {
+    ($day:expr, $part:expr, $variant:expr, $module:ident :: $generator:ident => $solve:ident with params and observer) => {
+        Solver {
+            day: $day,
+            part: $part,
+            variant: $variant,
+            runner: |input, config, reporter| {
+                let params = config.params::<::$module::Params>($day)?;
+                let start = Instant::now();
+                let parsed = ::$module::$generator(input)?;
+                let generated = Instant::now();
+                let answer = ::$module::$solve(&parsed, &params, reporter).into_answer()?;
+                Ok(Run {
+                    answer,
+                    generator_time: generated - start,
+                    solver_time: generated.elapsed(),
+                })
+            },
+        }
+    };
+    ($day:expr, $part:expr, $variant:expr, $module:ident :: $solve:ident with params and observer) => {
+        Solver {
+            day: $day,
+            part: $part,
+            variant: $variant,
+            runner: |input, config, reporter| {
+                let params = config.params::<::$module::Params>($day)?;
+                let start = Instant::now();
+                let answer = ::$module::$solve(input, &params, reporter).into_answer()?;
+                Ok(Run {
+                    answer,
+                    generator_time: Duration::default(),
+                    solver_time: start.elapsed(),
+                })
+            },
+        }
+    };
+    ($day:expr, $part:expr, $variant:expr, $module:ident :: $generator:ident => $solve:ident with params) => {
+        Solver {
+            day: $day,
+            part: $part,
+            variant: $variant,
+            runner: |input, config, _reporter| {
+                let params = config.params::<::$module::Params>($day)?;
+                let start = Instant::now();
+                let parsed = ::$module::$generator(input)?;
+                let generated = Instant::now();
+                let answer = ::$module::$solve(&parsed, &params).into_answer()?;
+                Ok(Run {
+                    answer,
+                    generator_time: generated - start,
+                    solver_time: generated.elapsed(),
+                })
+            },
+        }
+    };
+    ($day:expr, $part:expr, $variant:expr, $module:ident :: $solve:ident with params) => {
+        Solver {
+            day: $day,
+            part: $part,
+            variant: $variant,
+            runner: |input, config, _reporter| {
+                let params = config.params::<::$module::Params>($day)?;
+                let start = Instant::now();
+                let answer = ::$module::$solve(input, &params).into_answer()?;
+                Ok(Run {
+                    answer,
+                    generator_time: Duration::default(),
+                    solver_time: start.elapsed(),
+                })
+            },
+        }
+    };
+    ($day:expr, $part:expr, $variant:expr, $module:ident :: $generator:ident => $solve:ident) => {
+        Solver {
+            day: $day,
+            part: $part,
+            variant: $variant,
+            runner: |input, _config, _reporter| {
+                let start = Instant::now();
+                let parsed = ::$module::$generator(input)?;
+                let generated = Instant::now();
+                let answer = ::$module::$solve(&parsed).into_answer()?;
+                Ok(Run {
+                    answer,
+                    generator_time: generated - start,
+                    solver_time: generated.elapsed(),
+                })
+            },
+        }
+    };
+    ($day:expr, $part:expr, $variant:expr, $module:ident :: $solve:ident) => {
+        Solver {
+            day: $day,
+            part: $part,
+            variant: $variant,
+            runner: |input, _config, _reporter| {
+                let start = Instant::now();
+                let answer = ::$module::$solve(input).into_answer()?;
+                Ok(Run {
+                    answer,
+                    generator_time: Duration::default(),
+                    solver_time: start.elapsed(),
+                })
+            },
+        }
+    };
+}
+
+/// All solvers of the enabled days, ordered by day and part
+pub fn solvers() -> Vec<Solver> {
+    let mut solvers = Vec::new();
+
+    #[cfg(feature = "day01")]
+    solvers.extend(vec![
+        solver!(1, 1, None, day01::generator => solve_part1),
+        solver!(1, 2, None, day01::generator => solve_part2_with with params),
+        solver!(1, 2, Some("analytical"), day01::generator => solve_part2_analytical),
+    ]);
+    #[cfg(feature = "day02")]
+    solvers.extend(vec![
+        solver!(2, 1, None, day02::solve_part1),
+        solver!(2, 2, None, day02::solve_part2),
+        solver!(2, 2, Some("pairwise"), day02::solve_part2_pairwise),
+    ]);
+    #[cfg(feature = "day03")]
+    solvers.extend(vec![
+        solver!(3, 1, None, day03::generator => solve_part1),
+        solver!(3, 2, None, day03::generator => solve_part2),
+    ]);
+    #[cfg(feature = "day04")]
+    solvers.extend(vec![
+        solver!(4, 1, Some("hashmap"), day04::generator => solve_part1),
+        solver!(4, 1, Some("vec"), day04::generator => solve_part1_vec),
+        solver!(4, 2, Some("hashmap"), day04::generator => solve_part2),
+        solver!(4, 2, Some("vec"), day04::generator => solve_part2_vec),
+    ]);
+    #[cfg(feature = "day05")]
+    solvers.extend(vec![
+        solver!(5, 1, None, day05::solve_part1),
+        solver!(5, 2, None, day05::solve_part2),
+        solver!(5, 2, Some("iterator"), day05::solve_part2_iterator),
+        solver!(5, 2, Some("rayon"), day05::solve_part2_par),
+        solver!(
+            5,
+            2,
+            Some("early_collapse"),
+            day05::solve_part2_early_collapse
+        ),
+        solver!(
+            5,
+            2,
+            Some("rayon_early_collapse"),
+            day05::solve_part2_par_early_collapse
+        ),
+    ]);
+    #[cfg(feature = "day06")]
+    solvers.extend(vec![
+        solver!(6, 1, None, day06::generator => solve_part1),
+        solver!(6, 1, Some("rayon"), day06::generator => solve_part1_rayon),
+        solver!(6, 2, None, day06::generator => solve_part2_with with params),
+    ]);
+    #[cfg(feature = "day07")]
+    solvers.extend(vec![
+        solver!(7, 1, None, day07::generator => solve_part1),
+        solver!(7, 2, None, day07::generator => solve_part2_observed with params and observer),
+    ]);
+    #[cfg(feature = "day08")]
+    solvers.extend(vec![
+        solver!(8, 1, None, day08::generator => solve_part1),
+        solver!(8, 2, None, day08::generator => solve_part2),
+    ]);
+    #[cfg(feature = "day09")]
+    solvers.extend(vec![
+        solver!(
+            9,
+            1,
+            Some("vector"),
+            day09::generator => solve_part1_observed with params and observer
+        ),
+        solver!(
+            9,
+            1,
+            Some("linked_list"),
+            day09::generator => solve_part1_linked_list_observed with params and observer
+        ),
+        solver!(9, 2, None, day09::generator => solve_part2_observed with params and observer),
+    ]);
+    #[cfg(feature = "day10")]
+    solvers.extend(vec![
+        solver!(10, 1, None, day10::generator => solve_part1_with with params),
+    ]);
+    #[cfg(feature = "day11")]
+    solvers.extend(vec![
+        solver!(11, 1, None, day11::generator => solve_part1_with with params),
+        solver!(11, 2, None, day11::generator => solve_part2_with with params),
+    ]);
+    #[cfg(feature = "day12")]
+    solvers.extend(vec![
+        solver!(12, 1, None, day12::generator => solve_part1_with with params),
+        solver!(12, 2, None, day12::generator => solve_part2_with with params),
+    ]);
+    #[cfg(feature = "day13")]
+    solvers.extend(vec![
+        solver!(13, 1, None, day13::generator => solve_part1_observed with params and observer),
+        solver!(13, 2, None, day13::generator => solve_part2_observed with params and observer),
+    ]);
+    #[cfg(feature = "day14")]
+    solvers.extend(vec![
+        solver!(14, 1, None, day14::generator_part1 => solve_part1),
+        solver!(14, 2, None, day14::generator_part2 => solve_part2_observed with params and observer),
+    ]);
+
+    solvers
+}
+
+/// Check that all overrides in `config` belong to enabled days and name existing parameters
+pub fn validate_config(config: &Config) -> Result<(), ConfigError> {
+    for day in config.days() {
+        match day {
+            #[cfg(feature = "day01")]
+            1 => config.params::<::day01::Params>(day).map(|_| ())?,
+            #[cfg(feature = "day06")]
+            6 => config.params::<::day06::Params>(day).map(|_| ())?,
+            #[cfg(feature = "day07")]
+            7 => config.params::<::day07::Params>(day).map(|_| ())?,
+            #[cfg(feature = "day09")]
+            9 => config.params::<::day09::Params>(day).map(|_| ())?,
+            #[cfg(feature = "day10")]
+            10 => config.params::<::day10::Params>(day).map(|_| ())?,
+            #[cfg(feature = "day11")]
+            11 => config.params::<::day11::Params>(day).map(|_| ())?,
+            #[cfg(feature = "day12")]
+            12 => config.params::<::day12::Params>(day).map(|_| ())?,
+            #[cfg(feature = "day13")]
+            13 => config.params::<::day13::Params>(day).map(|_| ())?,
+            #[cfg(feature = "day14")]
+            14 => config.params::<::day14::Params>(day).map(|_| ())?,
+            _ => return Err(ConfigError::NoParameters(day)),
+        }
+    }
+    Ok(())
+}
+
+/// All variants of one part of a day, the default variant is always first
+pub fn variants(day: u8, part: u8) -> Vec<Solver> {
+    let mut variants: Vec<_> = solvers()
+        .into_iter()
+        .filter(|s| s.day == day && s.part == part)
+        .collect();
+    // stable sort keeps the registration order for the named variants
+    variants.sort_by_key(|s| s.variant.is_some());
+    variants
+}
+
+/// Find a solver by day, part and variant
+///
+/// Without a variant, the default one is returned or the first registered if there is no default.
+pub fn find(day: u8, part: u8, variant: Option<&str>) -> Option<Solver> {
+    let variants = variants(day, part);
+    match variant {
+        Some(name) => variants.into_iter().find(|s| s.variant_name() == name),
+        None => variants.into_iter().next(),
+    }
+}
+
+/// Answers of all variants of one part for the same input
+pub struct CrossCheck {
+    pub day: u8,
+    pub part: u8,
+    /// Variant name and the answer or error of that variant
+    pub results: Vec<(&'static str, Result<String, SolverError>)>,
+}
+
+impl CrossCheck {
+    /// The answer all variants agree on
+    ///
+    /// Returns `None` if any variant failed, the answers differ, or there are no variants at all.
+    pub fn agreed_answer(&self) -> Option<&str> {
+        let mut answers = self.results.iter().map(|(_, res)| res.as_ref().ok());
+        let first = answers.next()??;
+        if answers.all(|answer| answer == Some(first)) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
+
+/// Run all `solvers` on the same input and collect their answers
+pub fn check_variants(
+    day: u8,
+    part: u8,
+    solvers: &[Solver],
+    input: &str,
+    config: &Config,
+) -> CrossCheck {
+    CrossCheck {
+        day,
+        part,
+        results: solvers
+            .iter()
+            .map(|solver| {
+                let answer = solver
+                    .run_with(input, config)
+                    .map(|run| run.answer.to_string());
+                (solver.variant_name(), answer)
+            })
+            .collect(),
+    }
+}
+
+/// Run all variants of one part on the same input and collect their answers
+pub fn cross_check(day: u8, part: u8, input: &str, config: &Config) -> CrossCheck {
+    check_variants(day, part, &variants(day, part), input, config)
+}
+
+/// Outcome of a single solver of `run_all`
+#[derive(Debug)]
+pub struct SolverRun {
+    pub day: u8,
+    pub part: u8,
+    pub variant: &'static str,
+    /// The answer and timings, or the error message if the input could not be read or the solver failed
+    pub result: Result<Run, String>,
+}
+
+impl SolverRun {
+    /// Time spent in the generator and the solver, zero if the run failed
+    pub fn time(&self) -> Duration {
+        self.result
+            .as_ref()
+            .map(|run| run.generator_time + run.solver_time)
+            .unwrap_or_default()
+    }
+}
+
+/// Run `solvers` concurrently, each on the input `read_input` returns for its day
+///
+/// The input of each day is only read once. The results are sorted by time, slowest first.
+pub fn run_solvers<F>(solvers: &[Solver], config: &Config, read_input: F) -> Vec<SolverRun>
+where
+    F: Fn(u8) -> io::Result<String>,
+{
+    let mut inputs = HashMap::new();
+    for solver in solvers {
+        inputs.entry(solver.day).or_insert_with(|| {
+            read_input(solver.day).map_err(|err| format!("cannot read input: {}", err))
+        });
+    }
+
+    let mut runs: Vec<_> = solvers
+        .par_iter()
+        .map(|solver| {
+            let result = match inputs[&solver.day] {
+                Ok(ref input) => solver
+                    .run_with(input, config)
+                    .map_err(|err| err.to_string()),
+                Err(ref err) => Err(err.clone()),
+            };
+            SolverRun {
+                day: solver.day,
+                part: solver.part,
+                variant: solver.variant_name(),
+                result,
+            }
+        })
+        .collect();
+    // stable sort keeps the registration order for equal times
+    runs.sort_by_key(|run| Reverse(run.time()));
+    runs
+}
+
+/// Run every variant of every part of the enabled days concurrently
+pub fn run_all<F>(config: &Config, read_input: F) -> Vec<SolverRun>
+where
+    F: Fn(u8) -> io::Result<String>,
+{
+    run_solvers(&solvers(), config, read_input)
+}
+
+#[cfg(feature = "day04")]
+#[test]
+fn test_find_without_default() {
+    let solver = find(4, 1, None).unwrap();
+    assert_eq!(solver.variant, Some("hashmap"));
+    assert!(find(4, 1, Some("vec")).is_some());
+    assert!(find(4, 1, Some("missing")).is_none());
+}
+
+#[cfg(feature = "day03")]
+#[test]
+fn test_run_parse_error() {
+    let solver = find(3, 1, None).unwrap();
+    assert_eq!(solver.run("#1 @ 1,3: 4x4\n").unwrap(), "0");
+    let err = solver.run("#1 @ 1,3 4x4\n").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"day 3, line 1, column 9: expected ":", found " ""#
+    );
+}
+
+#[cfg(feature = "day04")]
+#[test]
+fn test_cross_check_agree() {
+    let input = "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+";
+    let check = cross_check(4, 1, input, &Config::default());
+    assert_eq!(check.results.len(), 2);
+    assert_eq!(check.agreed_answer(), Some("240"));
+}
+
+#[cfg(test)]
+fn test_run(answer: usize) -> Run {
+    Run {
+        answer: Answer::from(answer),
+        generator_time: Duration::default(),
+        solver_time: Duration::default(),
+    }
+}
+
+#[cfg(feature = "day13")]
+#[test]
+fn test_run_timed() {
+    let input = r"/->-\
+|   |  /----\
+| /-+--+-\  |
+| | |  | v  |
+\-+-/  \-+--/
+  \------/";
+    let run = find(13, 1, None).unwrap().run_timed(input).unwrap();
+    assert_eq!(run.answer, Answer::Point(::geometry::Point::new(7, 3)));
+    assert_eq!(run.answer.to_json(), r#"{"x":7,"y":3}"#);
+}
+
+#[cfg(feature = "day13")]
+#[test]
+fn test_run_observed() {
+    let input = r"/>-<\
+|   |
+| /<+-\
+| | | v
+\>+</ |
+  |   ^
+  \<->/";
+    let mut reports = Vec::new();
+    {
+        let mut callback = |steps: usize, summary: &str| reports.push((steps, summary.to_string()));
+        let mut reporter = Reporter::new(2, &mut callback);
+        let solver = find(13, 2, None).unwrap();
+        let run = solver
+            .run_observed(input, &Config::default(), &mut reporter)
+            .unwrap();
+        assert_eq!(run.answer, Answer::Point(::geometry::Point::new(6, 4)));
+    }
+    assert_eq!(
+        reports,
+        vec![
+            (0, "9 carts, 0 crashes".to_string()),
+            (2, "3 carts, 3 crashes".to_string()),
+        ]
+    );
+}
+
+#[cfg(feature = "day07")]
+#[test]
+fn test_run_with_config() {
+    let input = "Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.";
+    let mut config = Config::new();
+    config.parse_assignment("day7.workers=2").unwrap();
+    config.parse_assignment("day7.base_step_cost=0").unwrap();
+    assert_eq!(validate_config(&config), Ok(()));
+    let solver = find(7, 2, None).unwrap();
+    let run = solver.run_with(input, &config).unwrap();
+    assert_eq!(run.answer, Answer::Integer(15));
+
+    config.set(7, "elves", "2");
+    let err = solver.run_with(input, &config).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "day 7 has no parameter `elves`, expected one of: workers, base_step_cost"
+    );
+    assert!(validate_config(&config).is_err());
+    config = Config::new();
+    config.set(2, "start", "0");
+    assert_eq!(validate_config(&config), Err(ConfigError::NoParameters(2)));
+}
+
+#[test]
+fn test_cross_check_mismatch() {
+    let solvers = [
+        Solver {
+            day: 0,
+            part: 1,
+            variant: None,
+            runner: |input, _config, _reporter| Ok(test_run(input.len())),
+        },
+        Solver {
+            day: 0,
+            part: 1,
+            variant: Some("off_by_one"),
+            runner: |input, _config, _reporter| Ok(test_run(input.len() + 1)),
+        },
+    ];
+    let config = Config::default();
+    let check = check_variants(0, 1, &solvers, "abc", &config);
+    assert_eq!(check.agreed_answer(), None);
+    assert_eq!(check.results[1].0, "off_by_one");
+    assert_eq!(check.results[1].1.as_ref().unwrap(), "4");
+    assert_eq!(
+        check_variants(0, 1, &solvers[..1], "abc", &config).agreed_answer(),
+        Some("3")
+    );
+}
+
+#[test]
+fn test_run_solvers() {
+    let solvers = [
+        Solver {
+            day: 0,
+            part: 1,
+            variant: None,
+            runner: |input, _config, _reporter| Ok(test_run(input.len())),
+        },
+        Solver {
+            day: 0,
+            part: 2,
+            variant: None,
+            runner: |input, _config, _reporter| {
+                ::std::thread::sleep(Duration::from_millis(20));
+                let mut run = test_run(input.len() * 2);
+                run.solver_time = Duration::from_millis(20);
+                Ok(run)
+            },
+        },
+        Solver {
+            day: 1,
+            part: 1,
+            variant: None,
+            runner: |input, _config, _reporter| Ok(test_run(input.len())),
+        },
+    ];
+    let runs = run_solvers(&solvers, &Config::default(), |day| match day {
+        0 => Ok("abc".to_string()),
+        _ => Err(io::Error::new(io::ErrorKind::NotFound, "not found")),
+    });
+    let order: Vec<_> = runs.iter().map(|run| (run.day, run.part)).collect();
+    assert_eq!(order, vec![(0, 2), (0, 1), (1, 1)]);
+    assert_eq!(runs[0].result.as_ref().unwrap().answer, Answer::Integer(6));
+    assert_eq!(runs[0].time(), Duration::from_millis(20));
+    assert_eq!(
+        runs[2].result.as_ref().unwrap_err(),
+        "cannot read input: not found"
+    );
+}
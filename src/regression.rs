@@ -0,0 +1,180 @@
+//! Check all solvers against the known answers for the stored inputs
+
+use registry::{self, Run};
+use std::{io, time::Duration};
+
+/// The correct answer for one part of a day, for the input stored at `input`
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ExpectedAnswer {
+    pub day: u8,
+    pub part: u8,
+    /// Path of the input file, relative to the repository root
+    pub input: String,
+    pub answer: String,
+}
+
+/// Parse the answers file
+///
+/// Each line has the format `<day> <part> <input path> <answer>`. Newlines and backslashes in the
+/// answer are escaped as `\n` and `\\`. Empty lines and lines starting with `#` are ignored.
+pub fn parse_answers(text: &str) -> Result<Vec<ExpectedAnswer>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(idx, line)| {
+            let err = |msg: &str| format!("answers line {}: {}", idx + 1, msg);
+            let mut parts = line.splitn(4, ' ');
+            let day = parts
+                .next()
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(|| err("expected a day"))?;
+            let part = parts
+                .next()
+                .and_then(|p| p.parse().ok())
+                .ok_or_else(|| err("expected a part"))?;
+            let input = parts.next().ok_or_else(|| err("expected an input path"))?;
+            let answer = parts.next().ok_or_else(|| err("expected an answer"))?;
+            Ok(ExpectedAnswer {
+                day,
+                part,
+                input: input.to_string(),
+                answer: unescape(answer),
+            })
+        })
+        .collect()
+}
+
+/// Format an answer such that it fits on a single line of the answers file
+pub fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut res = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => res.push('\n'),
+                Some(other) => res.push(other),
+                None => res.push('\\'),
+            }
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
+
+/// Outcome of running one solver variant on one stored input
+#[derive(Debug)]
+pub struct RegressionResult {
+    pub day: u8,
+    pub part: u8,
+    pub variant: &'static str,
+    pub input: String,
+    pub expected: String,
+    /// The answer and timings of the solver, or the error message if it failed
+    pub actual: Result<Run, String>,
+}
+
+impl RegressionResult {
+    pub fn passed(&self) -> bool {
+        match self.actual {
+            Ok(ref run) => run.answer.to_string() == self.expected,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Run every variant of every solver which has an expected answer
+///
+/// `read_input` loads the input file for the path stored in the answers file.
+/// Expected answers for days which are not enabled are skipped.
+pub fn run<F>(answers: &[ExpectedAnswer], read_input: F) -> Vec<RegressionResult>
+where
+    F: Fn(&str) -> io::Result<String>,
+{
+    let mut results = Vec::new();
+    for expected in answers {
+        let variants = registry::variants(expected.day, expected.part);
+        if variants.is_empty() {
+            continue;
+        }
+        let input = read_input(&expected.input);
+        for solver in variants {
+            let actual = match input {
+                Ok(ref input) => solver.run_timed(input).map_err(|err| err.to_string()),
+                Err(ref err) => Err(format!("cannot read input: {}", err)),
+            };
+            results.push(RegressionResult {
+                day: expected.day,
+                part: expected.part,
+                variant: solver.variant_name(),
+                input: expected.input.clone(),
+                expected: expected.answer.clone(),
+                actual,
+            });
+        }
+    }
+    results
+}
+
+/// Duration in milliseconds, for printing
+pub fn millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1e3 + f64::from(duration.subsec_nanos()) / 1e6
+}
+
+#[test]
+fn test_parse_answers() {
+    let text = "# day part input answer
+1 1 input/2018/day1.txt 538
+
+10 1 input/2018/day10.txt \\n#  #\\n#\\\\#
+";
+    let answers = parse_answers(text).unwrap();
+    assert_eq!(answers.len(), 2);
+    assert_eq!(
+        answers[0],
+        ExpectedAnswer {
+            day: 1,
+            part: 1,
+            input: "input/2018/day1.txt".to_string(),
+            answer: "538".to_string(),
+        }
+    );
+    assert_eq!(answers[1].answer, "\n#  #\n#\\#");
+    assert_eq!(escape(&answers[1].answer), "\\n#  #\\n#\\\\#");
+
+    assert_eq!(
+        parse_answers("1 x input 5").unwrap_err(),
+        "answers line 1: expected a part"
+    );
+}
+
+#[cfg(feature = "day01")]
+#[test]
+fn test_run() {
+    let answers = parse_answers(
+        "1 1 a.txt 4
+1 2 a.txt 5
+1 1 missing.txt 4",
+    )
+    .unwrap();
+    let results = run(&answers, |path| {
+        if path == "a.txt" {
+            Ok("+3\n+3\n+4\n-2\n-4\n".to_string())
+        } else {
+            Err(io::Error::new(io::ErrorKind::NotFound, "not found"))
+        }
+    });
+    let passed: Vec<_> = results.iter().map(RegressionResult::passed).collect();
+    // Part 2 has two variants, which both run
+    assert_eq!(passed, vec![true, false, false, false]);
+    assert_eq!(results[1].actual.as_ref().unwrap().answer.to_string(), "10");
+    assert_eq!(results[2].actual.as_ref().unwrap().answer.to_string(), "10");
+    assert_eq!(
+        results[3].actual.as_ref().unwrap_err(),
+        "cannot read input: not found"
+    );
+}
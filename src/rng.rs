@@ -0,0 +1,87 @@
+//! Small, seedable pseudo random number generator
+//!
+//! The same seed always produces the same numbers, on all platforms and in all versions,
+//! which makes the generated inputs reproducible.
+
+/// SplitMix64 generator, see <http://prng.di.unimi.it/splitmix64.c>
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly distributed number in `0..n`
+    ///
+    /// Panics if `n` is 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "The range must not be empty");
+        ((u128::from(self.next_u64()) * u128::from(n)) >> 64) as u64
+    }
+
+    /// Uniformly distributed index for a collection of length `len`
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// Uniformly distributed number in `low..=high`
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "The range must not be empty");
+        let width = (high - low) as u64;
+        if width == u64::MAX {
+            self.next_u64() as i64
+        } else {
+            low + self.below(width + 1) as i64
+        }
+    }
+
+    /// `true` with probability `numerator / denominator`
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    /// Fisher-Yates shuffle
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.index(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[test]
+fn test_rng() {
+    // Reference values of the C implementation for seed 1234567
+    let mut rng = Rng::new(1_234_567);
+    assert_eq!(rng.next_u64(), 6_457_827_717_110_365_317);
+    assert_eq!(rng.next_u64(), 3_203_168_211_198_807_973);
+
+    let mut rng = Rng::new(42);
+    for _ in 0..1000 {
+        assert!(rng.below(7) < 7);
+        let r = rng.range(-3, 3);
+        assert!((-3..=3).contains(&r));
+    }
+    let mut items: Vec<_> = (0..10).collect();
+    rng.shuffle(&mut items);
+    let mut sorted = items.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+    assert_eq!(Rng::new(7), Rng::new(7));
+    assert_eq!(Rng::new(7).next_u64(), Rng::new(7).next_u64());
+}
@@ -0,0 +1,360 @@
+//! Step driven simulations, like the marble game or the mine carts
+//!
+//! A type implements `Simulation` by providing a single `step`.
+//! The provided methods run a number of steps, run until a condition holds, or record the history.
+//! Simulations implementing `StateKey` can additionally detect cycles and skip over them.
+//! The searching methods have `_within` variants, which give up once their `Budget` is used up.
+//! `run_until_observed` additionally reports the progress to an `observer::Observer`.
+
+use budget::{Budget, BudgetExceeded};
+use hashbrown::HashMap;
+use observer::{Observer, Silent};
+use std::hash::Hash;
+
+pub trait Simulation {
+    /// Advance the simulation by a single step
+    fn step(&mut self);
+
+    /// Whether the simulation reached a terminal state, in which `step` must not be called anymore
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    /// Perform up to `steps` steps, stopping early if the simulation finishes
+    ///
+    /// Returns the number of steps performed.
+    fn run(&mut self, steps: usize) -> usize
+    where
+        Self: Sized,
+    {
+        for done in 0..steps {
+            if self.is_finished() {
+                return done;
+            }
+            self.step();
+        }
+        steps
+    }
+
+    /// Step until `predicate` holds, the current state is checked first
+    ///
+    /// Returns the number of steps performed, or `None` if the simulation finished before.
+    fn run_until<P>(&mut self, predicate: P) -> Option<usize>
+    where
+        Self: Sized,
+        P: FnMut(&Self) -> bool,
+    {
+        match self.run_until_within(&Budget::unlimited(), predicate) {
+            Ok(res) => res,
+            Err(_) => unreachable!("An unlimited budget cannot be exceeded"),
+        }
+    }
+
+    /// Like `run_until`, but fail once the `budget` is used up
+    fn run_until_within<P>(
+        &mut self,
+        budget: &Budget,
+        predicate: P,
+    ) -> Result<Option<usize>, BudgetExceeded>
+    where
+        Self: Sized,
+        P: FnMut(&Self) -> bool,
+    {
+        self.run_until_observed(budget, predicate, &mut Silent)
+    }
+
+    /// Like `run_until_within`, but report the state to `observer` every few steps
+    fn run_until_observed<P, O>(
+        &mut self,
+        budget: &Budget,
+        mut predicate: P,
+        observer: &mut O,
+    ) -> Result<Option<usize>, BudgetExceeded>
+    where
+        Self: Sized,
+        P: FnMut(&Self) -> bool,
+        O: Observer<Self>,
+    {
+        let mut tracker = budget.start();
+        let interval = observer.interval().max(1);
+        for done in 0.. {
+            if done % interval == 0 {
+                observer.observe(done, self);
+            }
+            if predicate(self) {
+                return Ok(Some(done));
+            }
+            if self.is_finished() {
+                return Ok(None);
+            }
+            tracker.step()?;
+            self.step();
+        }
+        unreachable!()
+    }
+
+    /// Step until the simulation finishes and return the number of steps performed
+    fn run_to_end(&mut self) -> usize
+    where
+        Self: Sized,
+    {
+        self.run_until(Self::is_finished)
+            .expect("The predicate is checked before the simulation finishes")
+    }
+
+    /// Perform up to `steps` steps and return all states, starting with the current one
+    fn history(&mut self, steps: usize) -> Vec<Self>
+    where
+        Self: Sized + Clone,
+    {
+        let mut history = vec![self.clone()];
+        for _ in 0..steps {
+            if self.is_finished() {
+                break;
+            }
+            self.step();
+            history.push(self.clone());
+        }
+        history
+    }
+}
+
+/// A repetition found by `StateKey::find_cycle`
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Cycle {
+    /// Number of steps before the first state which repeats
+    pub start: usize,
+    pub length: usize,
+    /// Change of the offset with each pass through the cycle
+    pub shift: i64,
+}
+
+/// Simulations whose states can be compared, to find cycles
+pub trait StateKey: Simulation {
+    type Key: Eq + Hash;
+
+    /// Identifies the state, two states with the same key must evolve identically
+    ///
+    /// For simulations which repeat at a different position, the key should not contain the
+    /// position, but `offset` should return it.
+    fn key(&self) -> Self::Key;
+
+    /// Position of the state, states with the same key but different offsets are shifted copies
+    fn offset(&self) -> i64 {
+        0
+    }
+
+    /// Step until a state repeats and describe the cycle
+    ///
+    /// Afterwards, the simulation is in the first repeated state, i.e., `start + length` steps
+    /// were performed. Returns `None` if the simulation finishes before a state repeats.
+    fn find_cycle(&mut self) -> Option<Cycle>
+    where
+        Self: Sized,
+    {
+        let mut seen = HashMap::new();
+        for done in 0.. {
+            if let Some(&(start, offset)) = seen.get(&self.key()) {
+                return Some(Cycle {
+                    start,
+                    length: done - start,
+                    shift: self.offset() - offset,
+                });
+            }
+            if self.is_finished() {
+                return None;
+            }
+            seen.insert(self.key(), (done, self.offset()));
+            self.step();
+        }
+        unreachable!()
+    }
+
+    /// Advance by `steps` steps, but skip full passes through a cycle once one is found
+    ///
+    /// The state afterwards equals the state after `steps` steps, except for the offset.
+    /// Returns the shift of the offset caused by the skipped passes, which must be added to the
+    /// offset of the state. Stops early if the simulation finishes.
+    fn fast_forward(&mut self, steps: usize) -> i64
+    where
+        Self: Sized,
+    {
+        match self.fast_forward_within(steps, &Budget::unlimited()) {
+            Ok(shift) => shift,
+            Err(_) => unreachable!("An unlimited budget cannot be exceeded"),
+        }
+    }
+
+    /// Like `fast_forward`, but fail if the `budget` is used up before all steps are done
+    ///
+    /// Only the steps which are actually simulated count against the budget.
+    fn fast_forward_within(&mut self, steps: usize, budget: &Budget) -> Result<i64, BudgetExceeded>
+    where
+        Self: Sized,
+    {
+        let mut tracker = budget.start();
+        let mut seen = HashMap::new();
+        for done in 0..steps {
+            if self.is_finished() {
+                return Ok(0);
+            }
+            if let Some(&(start, offset)) = seen.get(&self.key()) {
+                let length = done - start;
+                let shift: i64 = self.offset() - offset;
+                let remaining = steps - done;
+                for _ in 0..remaining % length {
+                    if self.is_finished() {
+                        break;
+                    }
+                    tracker.step()?;
+                    self.step();
+                }
+                return Ok(shift * (remaining / length) as i64);
+            }
+            seen.insert(self.key(), (done, self.offset()));
+            tracker.step()?;
+            self.step();
+        }
+        Ok(0)
+    }
+}
+
+/// Counter which repeats `0, 1, ..., modulus - 1` and moves by `speed` each step
+#[cfg(test)]
+#[derive(Clone, Debug)]
+struct Counter {
+    value: i64,
+    modulus: i64,
+    speed: i64,
+    limit: Option<i64>,
+}
+
+#[cfg(test)]
+impl Simulation for Counter {
+    fn step(&mut self) {
+        self.value += self.speed;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.limit.is_some_and(|limit| self.value >= limit)
+    }
+}
+
+#[cfg(test)]
+impl StateKey for Counter {
+    type Key = i64;
+
+    fn key(&self) -> i64 {
+        self.value % self.modulus
+    }
+
+    fn offset(&self) -> i64 {
+        self.value / self.modulus * self.modulus
+    }
+}
+
+#[cfg(test)]
+fn counter(start: i64, limit: Option<i64>) -> Counter {
+    Counter {
+        value: start,
+        modulus: 10,
+        speed: 3,
+        limit,
+    }
+}
+
+#[test]
+fn test_run() {
+    let mut c = counter(0, Some(10));
+    assert_eq!(c.run(2), 2);
+    assert_eq!(c.value, 6);
+    assert_eq!(c.run(5), 2);
+    assert_eq!(c.value, 12);
+
+    let mut c = counter(0, None);
+    assert_eq!(c.run_until(|c| c.value > 10), Some(4));
+    assert_eq!(c.run_until(|c| c.value > 10), Some(0));
+    assert_eq!(counter(0, Some(10)).run_until(|c| c.value == 10), None);
+    assert_eq!(counter(0, Some(10)).run_to_end(), 4);
+
+    let history: Vec<_> = counter(0, Some(5))
+        .history(5)
+        .into_iter()
+        .map(|c| c.value)
+        .collect();
+    assert_eq!(history, vec![0, 3, 6]);
+}
+
+#[test]
+fn test_find_cycle() {
+    let mut c = counter(4, None);
+    let cycle = c.find_cycle().unwrap();
+    assert_eq!(
+        cycle,
+        Cycle {
+            start: 0,
+            length: 10,
+            shift: 30,
+        }
+    );
+    assert_eq!(c.value, 34);
+    assert_eq!(counter(0, Some(20)).find_cycle(), None);
+}
+
+#[test]
+fn test_budget() {
+    let budget = Budget::steps(3);
+    assert_eq!(
+        counter(0, None).run_until_within(&budget, |c| c.value == 9),
+        Ok(Some(3))
+    );
+    assert_eq!(
+        counter(0, None).run_until_within(&budget, |c| c.value == 12),
+        Err(BudgetExceeded::Steps(3))
+    );
+    assert_eq!(
+        counter(0, Some(5)).run_until_within(&budget, |c| c.value == 9),
+        Ok(None)
+    );
+    // The cycle is found after 10 steps, the remaining 2 steps are simulated
+    let budget = Budget::steps(12);
+    assert_eq!(
+        counter(4, None).fast_forward_within(1002, &budget),
+        Ok(2970)
+    );
+    assert_eq!(
+        counter(4, None).fast_forward_within(1009, &budget),
+        Err(BudgetExceeded::Steps(12))
+    );
+}
+
+#[test]
+fn test_run_until_observed() {
+    struct Every3(Vec<(usize, i64)>);
+    impl Observer<Counter> for Every3 {
+        fn interval(&self) -> usize {
+            3
+        }
+
+        fn observe(&mut self, steps: usize, state: &Counter) {
+            self.0.push((steps, state.value));
+        }
+    }
+
+    let mut observer = Every3(Vec::new());
+    let done =
+        counter(0, None).run_until_observed(&Budget::unlimited(), |c| c.value == 21, &mut observer);
+    assert_eq!(done, Ok(Some(7)));
+    assert_eq!(observer.0, vec![(0, 0), (3, 9), (6, 18)]);
+}
+
+#[test]
+fn test_fast_forward() {
+    for &steps in &[0, 5, 10, 23, 1234] {
+        let mut fast = counter(4, None);
+        let shift = fast.fast_forward(steps);
+        let mut slow = counter(4, None);
+        slow.run(steps);
+        assert_eq!(fast.value + shift, slow.value, "{} steps", steps);
+    }
+}
@@ -0,0 +1,173 @@
+//! Saving and resuming simulations
+//!
+//! A `Snapshot` is the state of a simulation together with the number of steps already performed.
+//! It is stored as JSON, such that a long simulation can be checkpointed, inspected by other tools,
+//! and resumed later.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json;
+use simulation::Simulation;
+use std::io::{Read, Write};
+
+/// A simulation state after `steps` steps
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub struct Snapshot<S> {
+    pub steps: usize,
+    pub state: S,
+}
+
+impl<S> Snapshot<S> {
+    /// Snapshot of a state, which was not simulated yet
+    pub fn new(state: S) -> Self {
+        Snapshot { steps: 0, state }
+    }
+}
+
+impl<S: Simulation> Snapshot<S> {
+    /// Perform up to `steps` more steps, stopping early if the simulation finishes
+    ///
+    /// Returns the number of steps performed.
+    pub fn run(&mut self, steps: usize) -> usize {
+        let done = self.state.run(steps);
+        self.steps += done;
+        done
+    }
+
+    /// Step until `predicate` holds, see `Simulation::run_until`
+    pub fn run_until<P>(&mut self, predicate: P) -> Option<usize>
+    where
+        P: FnMut(&S) -> bool,
+    {
+        let done = self.state.run_until(predicate)?;
+        self.steps += done;
+        Some(done)
+    }
+}
+
+impl<S: Serialize> Snapshot<S> {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn save<W: Write>(&self, writer: W) -> serde_json::Result<()> {
+        serde_json::to_writer(writer, self)
+    }
+}
+
+impl<S: DeserializeOwned> Snapshot<S> {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    pub fn load<R: Read>(reader: R) -> serde_json::Result<Self> {
+        serde_json::from_reader(reader)
+    }
+}
+
+/// Simulate `steps` steps, save and load the snapshot, and simulate the rest
+///
+/// Returns the resumed snapshot and the one simulated without interruption.
+#[cfg(test)]
+fn resume<S>(state: S, steps: usize, total: usize) -> (Snapshot<S>, Snapshot<S>)
+where
+    S: Simulation + Serialize + DeserializeOwned + Clone,
+{
+    let mut uninterrupted = Snapshot::new(state.clone());
+    uninterrupted.run(total);
+
+    let mut snapshot = Snapshot::new(state);
+    snapshot.run(steps);
+    let mut saved = Vec::new();
+    snapshot.save(&mut saved).unwrap();
+    let mut resumed: Snapshot<S> = Snapshot::load(&saved[..]).unwrap();
+    assert_eq!(resumed.steps, steps);
+    resumed.run(total - steps);
+    (resumed, uninterrupted)
+}
+
+#[cfg(feature = "day10")]
+#[test]
+fn test_resume_day10() {
+    use day10::{self, Sky};
+
+    let points = day10::generator(
+        "position=< 3,  9> velocity=< 1, -2>\nposition=< 7,  0> velocity=<-1,  0>",
+    )
+    .unwrap();
+    let (resumed, uninterrupted) = resume(Sky { points }, 2, 5);
+    let positions = |sky: &Sky| sky.points.iter().map(|p| p.position).collect::<Vec<_>>();
+    assert_eq!(positions(&resumed.state), positions(&uninterrupted.state));
+}
+
+#[cfg(feature = "day12")]
+#[test]
+fn test_resume_day12() {
+    use day12::{Pots, Rule};
+
+    // The simulation needs a rule for each of the 32 patterns, an empty pattern stays empty
+    let rules: Vec<_> = (0..32)
+        .map(|pattern| Rule {
+            pattern: (0..5).map(|i| pattern & (1 << i) != 0).collect(),
+            new_state: pattern % 3 == 1,
+        })
+        .collect();
+    let plants = [true, false, false, true, false, true];
+    let (resumed, uninterrupted) = resume(Pots::new(&plants, &rules), 3, 10);
+    assert_eq!(resumed, uninterrupted);
+}
+
+#[cfg(feature = "day13")]
+#[test]
+fn test_resume_day13() {
+    use day13::{self, Board};
+
+    let board = day13::generator(
+        r"/->-\
+|   |  /----\
+| /-+--+-\  |
+| | |  | v  |
+\-+-/  \-+--/
+  \------/",
+    )
+    .unwrap();
+    let (resumed, uninterrupted) = resume(board.clone(), 4, 20);
+    assert_eq!(resumed, uninterrupted);
+
+    // A resumed board can be solved like a freshly parsed one
+    let mut snapshot = Snapshot::new(board.clone());
+    snapshot.run(4);
+    let snapshot: Snapshot<Board> = Snapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+    assert_eq!(
+        day13::solve_part1(&snapshot.state),
+        day13::solve_part1(&board)
+    );
+}
+
+#[cfg(feature = "day14")]
+#[test]
+fn test_resume_day14() {
+    use day14::State;
+
+    let (resumed, uninterrupted) = resume(State::new(), 100, 250);
+    assert_eq!(resumed, uninterrupted);
+}
+
+#[cfg(feature = "day03")]
+#[test]
+fn test_claim_roundtrip() {
+    use day03::{self, Claim};
+
+    let claims = day03::generator("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4").unwrap();
+    let json = serde_json::to_string(&claims).unwrap();
+    assert_eq!(serde_json::from_str::<Vec<Claim>>(&json).unwrap(), claims);
+}
+
+#[cfg(feature = "day08")]
+#[test]
+fn test_tree_roundtrip() {
+    use day08::{self, Node};
+
+    let tree = day08::generator("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2").unwrap();
+    let json = serde_json::to_string(&tree).unwrap();
+    assert_eq!(serde_json::from_str::<Node>(&json).unwrap(), tree);
+}
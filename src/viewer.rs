@@ -0,0 +1,359 @@
+//! Watch simulations evolve in a terminal
+//!
+//! An `Animation` is a simulation which can be stepped, rewound, and rendered as text.
+//! The `Player` applies the user's `Command`s to it and builds the frames to draw.
+//! Only the terminal handling lives in the `aoc2018 view` command.
+
+use error::ParseError;
+use simulation::Simulation;
+use std::{str::FromStr, time::Duration};
+
+/// Highest speed in ticks per second
+pub const MAX_SPEED: u32 = 1024;
+
+/// Shortest time between two frames in milliseconds, faster speeds advance several ticks per frame
+pub const MIN_DELAY_MS: u64 = 20;
+
+/// A simulation which can jump to any tick and render its current state
+pub trait Animation {
+    /// Number of ticks simulated so far
+    fn tick(&self) -> usize;
+
+    /// Advance by one tick, returns `false` if the simulation already finished
+    fn step(&mut self) -> bool;
+
+    /// Go to `tick`, or to the last tick if the simulation finishes before
+    fn jump(&mut self, tick: usize);
+
+    fn render(&self) -> String;
+}
+
+/// Animation of any `Simulation`, which replays it from the start to go back in time
+pub struct Replay<S> {
+    initial: S,
+    state: S,
+    tick: usize,
+    render: fn(&S) -> String,
+}
+
+impl<S: Simulation + Clone> Replay<S> {
+    pub fn new(state: S, render: fn(&S) -> String) -> Self {
+        Replay {
+            initial: state.clone(),
+            state,
+            tick: 0,
+            render,
+        }
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S: Simulation + Clone> Animation for Replay<S> {
+    fn tick(&self) -> usize {
+        self.tick
+    }
+
+    fn step(&mut self) -> bool {
+        let done = self.state.run(1);
+        self.tick += done;
+        done == 1
+    }
+
+    fn jump(&mut self, tick: usize) {
+        if tick < self.tick {
+            self.state = self.initial.clone();
+            self.tick = 0;
+        }
+        self.tick += self.state.run(tick - self.tick);
+    }
+
+    fn render(&self) -> String {
+        (self.render)(&self.state)
+    }
+}
+
+/// Input of the user, one per line
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Command {
+    /// Start or stop playing
+    Toggle,
+    /// Advance by a single tick, an empty line
+    Step,
+    /// Double the speed
+    Faster,
+    /// Halve the speed
+    Slower,
+    /// Go to a tick, given as `j <tick>` or just the number
+    Jump(usize),
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim();
+        let command = match line {
+            "p" | "play" | "pause" => Command::Toggle,
+            "" | "s" | "step" => Command::Step,
+            "+" | "faster" => Command::Faster,
+            "-" | "slower" => Command::Slower,
+            "r" | "reset" => Command::Jump(0),
+            "q" | "quit" => Command::Quit,
+            _ => {
+                let tick = match line.strip_prefix("j ") {
+                    Some(tick) => tick.trim(),
+                    None => line,
+                };
+                let tick = tick
+                    .parse()
+                    .map_err(|_| format!("Unknown command `{}`", line))?;
+                Command::Jump(tick)
+            }
+        };
+        Ok(command)
+    }
+}
+
+/// Help shown below every frame
+pub const HELP: &str = "p: play/pause, enter: step, +/-: speed, j <tick>: jump, r: reset, q: quit";
+
+/// Playback state of an `Animation`
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Player {
+    pub playing: bool,
+    /// Ticks per second while playing, between 1 and `MAX_SPEED`
+    pub speed: u32,
+}
+
+impl Player {
+    /// A paused player, `speed` is limited to the allowed range
+    pub fn new(speed: u32) -> Self {
+        Player {
+            playing: false,
+            speed: speed.clamp(1, MAX_SPEED),
+        }
+    }
+
+    /// Apply `command` to `animation`, returns `false` for `Command::Quit`
+    pub fn apply(&mut self, animation: &mut dyn Animation, command: Command) -> bool {
+        match command {
+            Command::Toggle => self.playing = !self.playing,
+            Command::Step => {
+                self.playing = false;
+                animation.step();
+            }
+            Command::Faster => self.speed = (self.speed * 2).min(MAX_SPEED),
+            Command::Slower => self.speed = (self.speed / 2).max(1),
+            Command::Jump(tick) => {
+                self.playing = false;
+                animation.jump(tick);
+            }
+            Command::Quit => return false,
+        }
+        true
+    }
+
+    /// Advance `animation` by one frame while playing, stops playing once it finished
+    pub fn advance(&mut self, animation: &mut dyn Animation) {
+        if !self.playing {
+            return;
+        }
+        for _ in 0..self.ticks_per_frame() {
+            if !animation.step() {
+                self.playing = false;
+                return;
+            }
+        }
+    }
+
+    /// Time between two frames while playing, at least `MIN_DELAY_MS` to not redraw all the time
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis((1000 / u64::from(self.speed)).max(MIN_DELAY_MS))
+    }
+
+    /// Ticks to advance per frame, such that high speeds are kept despite `MIN_DELAY_MS`
+    pub fn ticks_per_frame(&self) -> u64 {
+        (u64::from(self.speed) * MIN_DELAY_MS / 1000).max(1)
+    }
+
+    /// Status line, rendering, and help of the current tick
+    pub fn frame(&self, animation: &dyn Animation) -> String {
+        format!(
+            "tick {}, {}, {} ticks/s\n\n{}\n\n{}",
+            animation.tick(),
+            if self.playing { "playing" } else { "paused" },
+            self.speed,
+            animation.render().trim_end(),
+            HELP
+        )
+    }
+}
+
+/// Animation of the simulation of `day`, or `None` if the day has no simulation to watch
+#[cfg_attr(
+    not(any(feature = "day10", feature = "day13")),
+    allow(unused_variables)
+)]
+pub fn for_day(day: u8, input: &str) -> Option<Result<Box<dyn Animation>, ParseError>> {
+    match day {
+        #[cfg(feature = "day10")]
+        10 => Some(::day10::generator(input).map(|points| {
+            let sky = ::day10::Sky { points };
+            Box::new(Replay::new(sky, render_sky)) as Box<dyn Animation>
+        })),
+        #[cfg(feature = "day13")]
+        13 => Some(::day13::generator(input).map(|board| {
+            Box::new(Replay::new(board, |board| board.to_string())) as Box<dyn Animation>
+        })),
+        #[cfg(feature = "day14")]
+        14 => {
+            // The scoreboard always starts with `3 7`, the input is only needed for the answer
+            Some(Ok(Box::new(Replay::new(
+                ::day14::State::new(),
+                render_scoreboard,
+            ))))
+        }
+        _ => None,
+    }
+}
+
+/// The message, or the size of the sky while the points are too far apart
+#[cfg(feature = "day10")]
+fn render_sky(sky: &::day10::Sky) -> String {
+    use geometry::BoundingBox;
+
+    ::day10::print_current_step(&sky.points).unwrap_or_else(|| {
+        match BoundingBox::from_points(sky.points.iter().map(|p| p.position)) {
+            Some(bbox) => format!(
+                "{} points spread over {}x{}",
+                sky.points.len(),
+                bbox.width(),
+                bbox.height()
+            ),
+            None => "no points".to_string(),
+        }
+    })
+}
+
+/// Receipies shown in full, longer scoreboards only show their end
+#[cfg(feature = "day14")]
+const SCOREBOARD_WIDTH: usize = 40;
+
+#[cfg(feature = "day14")]
+fn render_scoreboard(state: &::day14::State) -> String {
+    let len = state.receipies.len();
+    if len <= SCOREBOARD_WIDTH {
+        return state.to_string();
+    }
+    let last: String = state.receipies[len - SCOREBOARD_WIDTH..]
+        .iter()
+        .map(|r| r.to_string())
+        .collect();
+    format!(
+        "{} receipies, elves at {} and {}\n...{}",
+        len, state.first_elf, state.second_elf, last
+    )
+}
+
+/// Counts up to `limit`, rendered as its value
+#[cfg(test)]
+#[derive(Clone, Debug)]
+struct Counter {
+    value: usize,
+    limit: usize,
+}
+
+#[cfg(test)]
+impl Simulation for Counter {
+    fn step(&mut self) {
+        self.value += 1;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.value >= self.limit
+    }
+}
+
+#[cfg(test)]
+fn counter(limit: usize) -> Replay<Counter> {
+    Replay::new(Counter { value: 0, limit }, |c| c.value.to_string())
+}
+
+#[test]
+fn test_parse_command() {
+    assert_eq!("p".parse(), Ok(Command::Toggle));
+    assert_eq!("\n".parse(), Ok(Command::Step));
+    assert_eq!(" + ".parse(), Ok(Command::Faster));
+    assert_eq!("j 42".parse(), Ok(Command::Jump(42)));
+    assert_eq!("17".parse(), Ok(Command::Jump(17)));
+    assert_eq!("r".parse(), Ok(Command::Jump(0)));
+    assert_eq!(
+        "x".parse::<Command>(),
+        Err("Unknown command `x`".to_string())
+    );
+}
+
+#[test]
+fn test_replay() {
+    let mut replay = counter(10);
+    assert!(replay.step());
+    assert_eq!(replay.render(), "1");
+    replay.jump(7);
+    assert_eq!((replay.tick(), replay.state().value), (7, 7));
+    replay.jump(3);
+    assert_eq!((replay.tick(), replay.state().value), (3, 3));
+    replay.jump(20);
+    assert_eq!((replay.tick(), replay.state().value), (10, 10));
+    assert!(!replay.step());
+}
+
+#[test]
+fn test_player() {
+    let mut replay = counter(25);
+    let mut player = Player::new(5000);
+    assert_eq!(player.speed, MAX_SPEED);
+    assert!(player.apply(&mut replay, Command::Slower));
+    // 512 ticks per second are too fast to draw each tick
+    assert_eq!(player.delay(), Duration::from_millis(MIN_DELAY_MS));
+    assert_eq!(player.ticks_per_frame(), 10);
+    assert_eq!(Player::new(10).delay(), Duration::from_millis(100));
+    assert_eq!(Player::new(10).ticks_per_frame(), 1);
+    assert!(player.apply(&mut replay, Command::Toggle));
+    assert!(player.playing);
+    player.advance(&mut replay);
+    player.advance(&mut replay);
+    assert!(player.playing);
+    assert_eq!(replay.tick(), 20);
+    // The counter finished, which pauses the player
+    player.advance(&mut replay);
+    assert!(!player.playing);
+    assert_eq!(
+        player.frame(&replay),
+        format!("tick 25, paused, 512 ticks/s\n\n25\n\n{}", HELP)
+    );
+    assert!(player.apply(&mut replay, Command::Jump(1)));
+    assert_eq!(replay.tick(), 1);
+    assert!(!player.apply(&mut replay, Command::Quit));
+}
+
+#[cfg(feature = "day13")]
+#[test]
+fn test_for_day() {
+    let input = r"/->-\
+|   |  /----\
+| /-+--+-\  |
+| | |  | v  |
+\-+-/  \-+--/
+  \------/";
+    let mut animation = for_day(13, input).unwrap().unwrap();
+    animation.jump(2);
+    let mut board = ::day13::generator(input).unwrap();
+    board.run(2);
+    assert_eq!(animation.render(), board.to_string());
+    assert!(for_day(13, "/-#").unwrap().is_err());
+    assert!(for_day(1, "").is_none());
+}